@@ -6,8 +6,16 @@
 
 //! The highest abstraction of the application logic, including window creation.
 
+use std::env;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+use std::path::Path;
 use std::path::PathBuf;
 
+use find_folder::Error as FindFolderError;
 use find_folder::Search;
 #[cfg(feature = "display-fps")]
 use fps_counter::FPSCounter;
@@ -18,28 +26,158 @@ use piston_window::ButtonState;
 use piston_window::Event;
 use piston_window::Glyphs;
 use piston_window::Input;
+use piston_window::Key;
 use piston_window::Loop;
-use piston_window::OpenGL;
+use piston_window::Motion;
 use piston_window::PistonWindow;
 use piston_window::RenderArgs;
 use piston_window::TextureSettings;
+use piston_window::TouchArgs;
 use piston_window::Transformed;
 use piston_window::UpdateArgs;
 use piston_window::WindowSettings;
 #[cfg(feature = "display-fps")]
 use piston_window::text::Text;
+use rand::thread_rng;
 
+use camera::Camera;
+use config;
+use config::Config;
+use config::InputSource;
+use config::ScoreboardPosition;
+use controls;
+use effects::ScreenShake;
+use elements::BallStatus;
 use elements::Field;
+use elements::FieldSide;
+use elements::MatchStats;
 use elements::Scoreboard;
+use execution_flow::Error;
 use execution_flow::Result;
+use execution_flow::exit::Code;
+use pixels;
 use color;
 
-/// The OpenGL version.
-const OPENGL: OpenGL = OpenGL::V3_2;
+/// The environment variable that can be set to use a specific assets folder.
+const ASSETS_VARIABLE: &str = "MIEF_ASSETS";
+
+/// The command line argument that can be given to check the environment instead of starting the game.
+pub const CHECK_ARGUMENT: &str = "--check";
+
+/// The command line argument that can be given to run the game without a window for a number of frames and print the
+/// state of each frame as CSV instead of starting the game, e.g. `--dump 600` or `--dump=600`.
+pub const DUMP_ARGUMENT: &str = "--dump";
+
+/// The simulated time per frame when dumping a game (in seconds).
+const DUMP_FRAME_TIME: f64 = 1.0 / 60.0;
+
+/// The name of the file within the assets folder containing the key bindings.
+const CONTROLS: &str = "controls.toml";
+
+/// The name of the font file within the assets folder.
+const FONT: &str = "Anonymous Pro.ttf";
+
+/// The environment variable that can be set to let the ball shrink as it speeds up: `drawn` or `collision`.
+const BALL_SHRINK_VARIABLE: &str = "MIEF_BALL_SHRINK";
+
+/// The environment variable that can be set to select how the ball bounces off the handles: `reflect`, `angled`, or
+/// `segmented`.
+const BOUNCE_MODEL_VARIABLE: &str = "MIEF_BOUNCE";
+
+/// The environment variable that can be set to select the preset of the default key bindings.
+const CONTROLS_PRESET_VARIABLE: &str = "MIEF_CONTROLS_PRESET";
+
+/// The environment variable that can be set to select how the game becomes more difficult: `ramp` or `skill`.
+const DIFFICULTY_VARIABLE: &str = "MIEF_DIFFICULTY";
+
+/// The environment variable that can be set to limit the length of rallies to a number of hits (e.g. `20`) or seconds
+/// (e.g. `30s`).
+const RALLY_CAP_VARIABLE: &str = "MIEF_RALLY_CAP";
+
+/// The environment variable that can be set to place the scoreboard at the `top` or the `bottom` of the window.
+const SCOREBOARD_POSITION_VARIABLE: &str = "MIEF_SCOREBOARD";
+
+/// The environment variable that can be set to let the computer control the right player at a skill level, e.g. `easy`.
+const OPPONENT_VARIABLE: &str = "MIEF_OPPONENT";
+
+/// The environment variable that can be set to end the match once a player reaches a score (e.g. `11`) with a lead of
+/// at least two points.
+const TARGET_SCORE_VARIABLE: &str = "MIEF_TARGET_SCORE";
+
+/// The environment variable that can be set to use a specific OpenGL version, e.g. `3.2`.
+const OPENGL_VARIABLE: &str = "MIEF_OPENGL";
+
+/// The command line argument that can be given to use a specific seed for the game's random choices, e.g. `--seed 42`
+/// or `--seed=42`.
+const SEED_ARGUMENT: &str = "--seed";
 
 /// The (currently) fixed height of the scoreboard.
 const SCOREBOARD_HEIGHT: u32 = 120;
 
+/// The title of the window.
+const TITLE: &str = "Mief";
+
+/// The initial size of the window: `[width, height]`.
+const WINDOW_SIZE: [u32; 2] = [800, 600];
+
+/// The ways a match can end.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Outcome {
+    /// The players quit before any point was scored.
+    Abandoned,
+
+    /// The players quit after at least one point was scored.
+    Quit,
+}
+
+impl Outcome {
+    /// Determine how the match described by the `stats` ended once the players quit.
+    fn from_stats(stats: &MatchStats) -> Outcome {
+        if stats.points.iter().sum::<usize>() == 0 {
+            Outcome::Abandoned
+        }
+        else {
+            Outcome::Quit
+        }
+    }
+}
+
+impl From<Outcome> for Code {
+    fn from(outcome: Outcome) -> Code {
+        match outcome {
+            Outcome::Abandoned => Code::Abandoned,
+            Outcome::Quit => Code::Success,
+        }
+    }
+}
+
+/// Tells an event loop whether to continue with the next event.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum LoopControl {
+    /// Continue with the next event.
+    Continue,
+
+    /// Stop processing events.
+    Quit,
+}
+
+/// An event loop: a source of events and their handling, including a shutdown once all events are processed.
+trait EventLoop {
+    /// The type of the events.
+    type Event;
+
+    /// Get the next event, or `None` if there are no more events.
+    fn next_event(&mut self) -> Option<Self::Event>;
+
+    /// Handle the `event`.
+    ///
+    /// Returns whether to continue with the next event.
+    fn handle_event(&mut self, event: Self::Event) -> LoopControl;
+
+    /// Shut down after the last event has been handled.
+    fn on_shutdown(&mut self);
+}
+
 /// The manager of the application logic.
 pub struct Application {
     /// Path to the folder containing the assets.
@@ -54,6 +192,27 @@ pub struct Application {
     /// The scoreboard.
     scoreboard: Scoreboard,
 
+    /// The smallest and the largest size of the field: `[minimum, maximum]`, each given as `[width, height]`.
+    field_size_limits: [[u32; 2]; 2],
+
+    /// If set, the ratio of width to height kept by the play area (i.e. the field and the scoreboard).
+    aspect_ratio: Option<f64>,
+
+    /// The position and size of the play area within the window: `[x, y, width, height]`.
+    play_area: [u32; 4],
+
+    /// The position of the scoreboard relative to the field.
+    scoreboard_position: ScoreboardPosition,
+
+    /// Snap all drawn coordinates to whole device pixels.
+    pixel_snapping: bool,
+
+    /// The screen shake after a point has been scored.
+    screen_shake: ScreenShake,
+
+    /// If set, the camera zooming in on the field and following the ball.
+    camera: Option<Camera>,
+
     /// The FPS counter.
     #[cfg(feature = "display-fps")]
     fps_counter: FPSCounter,
@@ -62,20 +221,21 @@ pub struct Application {
 impl Application {
     /// Initialize a new application.
     ///
-    /// Returns an error if the `PistonWindow` cannot be initialized.
+    /// Returns an error if the settings are invalid or if the `PistonWindow` cannot be initialized.
     pub fn new() -> Result<Application> {
-        let width: u32 = 800;
-        let height: u32 = 600;
-        let title: &str = "Mief";
-
-        let window: PistonWindow = WindowSettings::new(title, [width, height])
-            .exit_on_esc(true)
-            .opengl(OPENGL)
-            .resizable(false)  // Not yet working - see https://github.com/PistonDevelopers/piston_window/issues/160.
-            .vsync(true)
-            .build()?;
+        let config: Config = load_config()?;
+        let [width, height]: [u32; 2] = WINDOW_SIZE;
+        let title: &str = TITLE;
+        let window: PistonWindow = create_window(&config)?;
+        let assets: PathBuf = find_assets(env::var_os(ASSETS_VARIABLE))?;
+        let mut field = Field::new([width, height - SCOREBOARD_HEIGHT], &config);
+        println!("Seed: {seed} (run with `{argument} {seed}` to reproduce this game)",
+                 seed = field.get_seed(), argument = SEED_ARGUMENT);
+        if let Err(error) = field.reload_controls(&assets.join(CONTROLS)) {
+            println!("Using the default controls: {description}", description = error);
+        }
 
-        let assets: PathBuf = Search::ParentsThenKids(3, 1).for_folder("assets")?;
+        let camera: Option<Camera> = config.camera_follow.map(|settings| Camera::new(settings, field.ball_center()));
 
         let application = match () {
             #[cfg(feature = "display-fps")]
@@ -83,8 +243,15 @@ impl Application {
                 Application {
                     assets,
                     window,
-                    field: Field::new([width, height - SCOREBOARD_HEIGHT]),
-                    scoreboard: Scoreboard::new([width, SCOREBOARD_HEIGHT], title),
+                    field,
+                    scoreboard: Scoreboard::new([width, SCOREBOARD_HEIGHT], title, &config),
+                    aspect_ratio: config.aspect_ratio,
+                    field_size_limits: [config.min_field_size, config.max_field_size],
+                    play_area: [0, 0, width, height],
+                    scoreboard_position: config.scoreboard_position,
+                    pixel_snapping: config.pixel_snapping,
+                    screen_shake: ScreenShake::default(),
+                    camera,
                     fps_counter: FPSCounter::new(),
                 }
             },
@@ -93,49 +260,61 @@ impl Application {
                 Application {
                     assets,
                     window,
-                    field: Field::new([width, height - SCOREBOARD_HEIGHT]),
-                    scoreboard: Scoreboard::new([width, SCOREBOARD_HEIGHT], title),
+                    field,
+                    scoreboard: Scoreboard::new([width, SCOREBOARD_HEIGHT], title, &config),
+                    aspect_ratio: config.aspect_ratio,
+                    field_size_limits: [config.min_field_size, config.max_field_size],
+                    play_area: [0, 0, width, height],
+                    scoreboard_position: config.scoreboard_position,
+                    pixel_snapping: config.pixel_snapping,
+                    screen_shake: ScreenShake::default(),
+                    camera,
                 }
             },
         };
         Ok(application)
     }
 
-    /// Handle button events.
-    fn on_button_change(&mut self, button_arguments: ButtonArgs) {
-        match button_arguments.state {
-            ButtonState::Press => self.on_button_pressed(button_arguments.button),
-            ButtonState::Release => self.on_button_released(button_arguments.button),
+    /// Reload the key bindings. If they cannot be loaded, the current ones are kept.
+    fn on_reload_controls(&mut self) {
+        if let Err(error) = self.field.reload_controls(&self.assets.join(CONTROLS)) {
+            println!("Keeping the current controls: {description}", description = error);
         }
     }
 
-    /// Handle button press events.
-    fn on_button_pressed(&mut self, button: Button) {
-        self.field.on_button_pressed(button);
-    }
-
-    /// Handle button release events.
-    fn on_button_released(&mut self, button: Button) {
-        self.field.on_button_released(button);
-    }
-
     /// Render the entire application.
-    fn on_render(&mut self, event: &Event, _render_arguments: &RenderArgs) {
-        let font: PathBuf = self.assets.join("Anonymous Pro.ttf");
+    fn on_render(&mut self, event: &Event, render_arguments: &RenderArgs) {
+        let font: PathBuf = self.assets.join(FONT);
         let factory = self.window.factory.clone();
         let texture_settings = TextureSettings::new();
         let mut font = Glyphs::new(font, factory, texture_settings).unwrap();
 
         let field: &Field = &self.field;
-        let scoreboard: &Scoreboard = &self.scoreboard;
+        let [field_width, field_height]: [u32; 2] = field.get_size();
+        let ((camera_x, camera_y), zoom): ((f64, f64), f64) = match self.camera {
+            Some(camera) => camera.transform([f64::from(field_width), f64::from(field_height)]),
+            None => ((0.0, 0.0), 1.0),
+        };
+        let scoreboard: &mut Scoreboard = &mut self.scoreboard;
+        let pixel_scale: Option<f64> = if self.pixel_snapping && render_arguments.width > 0 {
+            Some(f64::from(render_arguments.draw_width) / f64::from(render_arguments.width))
+        }
+        else {
+            None
+        };
+        let offset: (f64, f64) = pixels::snap_point(self.screen_shake.offset(&mut thread_rng()), pixel_scale);
+        let [area_x, area_y, _, _]: [u32; 4] = self.play_area;
+        let (field_y, scoreboard_y): (f64, f64) = vertical_offsets(field_height, self.scoreboard_position);
         #[cfg(feature = "display-fps")]
         let fps: &str = &self.fps_counter.tick().to_string();
 
         let _ = self.window.draw_2d(event, |context, gl_graphics| {
             clear(color::BLACK, gl_graphics);
 
-            field.on_render(context.trans(0.0, f64::from(SCOREBOARD_HEIGHT)), gl_graphics);
-            scoreboard.on_render(&mut font, context.trans(0.0, 0.0), gl_graphics);
+            let context = context.trans(f64::from(area_x) + offset.0, f64::from(area_y) + offset.1);
+            let field_context = context.trans(0.0, field_y).trans(camera_x, camera_y).zoom(zoom);
+            field.on_render(field_context, gl_graphics, pixel_scale);
+            scoreboard.on_render(&mut font, context.trans(0.0, scoreboard_y), gl_graphics, pixel_scale);
 
             #[cfg(feature = "display-fps")]
             {
@@ -148,38 +327,626 @@ impl Application {
         });
     }
 
-    /// Resize the application.
+    /// Resize the application, keeping the aspect ratio of the play area if one is set.
     fn on_resize(&mut self, new_width: u32, new_height: u32) {
-        self.field.on_resize(new_width, new_height - SCOREBOARD_HEIGHT);
-        self.scoreboard.on_resize(new_width, SCOREBOARD_HEIGHT);
+        self.play_area = play_area([new_width, new_height], self.aspect_ratio);
+        let [_, _, width, height]: [u32; 4] = self.play_area;
+        let requested_size: [u32; 2] = [width, height.saturating_sub(SCOREBOARD_HEIGHT)];
+        let [minimum, maximum]: [[u32; 2]; 2] = self.field_size_limits;
+        let field_size: [u32; 2] = clamp_field_size(requested_size, minimum, maximum);
+        // Only report the limit when it changes the field's size, not on every step of resizing the window.
+        if field_size != requested_size && field_size != self.field.get_size() {
+            println!("Limiting the field size to {width}x{height} instead of {requested_width}x{requested_height}.",
+                     width = field_size[0], height = field_size[1],
+                     requested_width = requested_size[0], requested_height = requested_size[1]);
+        }
+
+        self.field.on_resize(field_size[0], field_size[1]);
+        self.scoreboard.on_resize(width, SCOREBOARD_HEIGHT);
+    }
+
+    /// Pass a touch on to the field, translating its position from the window to the field.
+    fn on_touch(&mut self, touch_arguments: &TouchArgs) {
+        // Despite their documentation, touches are reported in window coordinates, not normalized ones.
+        let [area_x, area_y, _, _]: [u32; 4] = self.play_area;
+        let [_, field_height]: [u32; 2] = self.field.get_size();
+        let (field_y, _): (f64, f64) = vertical_offsets(field_height, self.scoreboard_position);
+        let position: [f64; 2] = [
+            touch_arguments.x - f64::from(area_x),
+            touch_arguments.y - f64::from(area_y) - field_y,
+        ];
+        self.field.on_touch(position, touch_arguments.touch);
     }
 
     /// Update the application state.
     fn on_update(&mut self, update_arguments: &UpdateArgs) {
-        self.field.on_update(update_arguments);
-        self.scoreboard.on_update(self.field.get_player_scores());
-    }
-
-    /// Run the application.
-    pub fn run(&mut self) {
-        while let Some(event) = self.window.next() {
-            match event {
-                Event::Input(input_event) => {
-                    match input_event {
-                        Input::Button(button_arguments) => self.on_button_change(button_arguments),
-                        Input::Resize(width, height) => self.on_resize(width, height),
-                        _ => {},
-                    }
-                },
-                Event::Loop(loop_event) => {
-                    match loop_event {
-                        Loop::Render(render_arguments) => self.on_render(&event, &render_arguments),
-                        Loop::Update(update_arguments) => self.on_update(&update_arguments),
-                        _ => {},
-                    }
-                },
-                _ => {},
+        self.screen_shake.update(update_arguments.dt);
+        if let Some(ref mut camera) = self.camera {
+            camera.update(self.field.ball_center(), update_arguments.dt);
+        }
+        let status: BallStatus = self.field.on_update(update_arguments);
+        if status == BallStatus::LeftOnLeftSide || status == BallStatus::LeftOnRightSide {
+            self.screen_shake.start();
+        }
+        self.scoreboard.on_update(self.field.get_player_scores(), self.field.get_serving_side());
+    }
+
+    /// Run the application until the window is closed.
+    ///
+    /// Returns how the match ended.
+    pub fn run(&mut self) -> Outcome {
+        run_event_loop(self);
+        Outcome::from_stats(&self.field.get_stats())
+    }
+}
+
+impl EventLoop for Application {
+    type Event = Event;
+
+    fn next_event(&mut self) -> Option<Event> {
+        self.window.next()
+    }
+
+    fn handle_event(&mut self, event: Event) -> LoopControl {
+        match event {
+            Event::Input(input_event) => {
+                match input_event {
+                    Input::Button(ButtonArgs {
+                        state: ButtonState::Press,
+                        button: Button::Keyboard(Key::F12),
+                        ..
+                    }) => println!("{}", self.field.summary()),
+                    Input::Button(ButtonArgs {
+                        state: ButtonState::Press,
+                        button: Button::Keyboard(Key::F5),
+                        ..
+                    }) => self.on_reload_controls(),
+                    Input::Move(Motion::Touch(touch_arguments)) => self.on_touch(&touch_arguments),
+                    Input::Close(_) => return LoopControl::Quit,
+                    Input::Resize(width, height) => self.on_resize(width, height),
+                    _ => self.field.handle_input(&input_event),
+                }
+            },
+            Event::Loop(loop_event) => {
+                match loop_event {
+                    Loop::Render(render_arguments) => self.on_render(&event, &render_arguments),
+                    Loop::Update(update_arguments) => self.on_update(&update_arguments),
+                    _ => {},
+                }
+            },
+            _ => {},
+        };
+
+        LoopControl::Continue
+    }
+
+    fn on_shutdown(&mut self) {
+        let scores: [isize; 2] = self.field.get_player_scores();
+        let stats: MatchStats = self.field.get_stats();
+        println!("Final score: {left} - {right}", left = scores[0], right = scores[1]);
+        match self.field.winner() {
+            Some(FieldSide::Left) => println!("The left player won the match."),
+            Some(FieldSide::Right) => println!("The right player won the match."),
+            None => {},
+        }
+        println!("Points won: {left} - {right}", left = stats.points[0], right = stats.points[1]);
+        println!("Aces: {left} - {right}", left = stats.aces[0], right = stats.aces[1]);
+        println!("Longest rally: {hits} hits", hits = stats.longest_rally);
+    }
+}
+
+/// Handle all events of the `event_loop` until there are no more events or an event requests to quit. Afterwards,
+/// shut the event loop down exactly once.
+fn run_event_loop<L: EventLoop>(event_loop: &mut L) {
+    while let Some(event) = event_loop.next_event() {
+        if event_loop.handle_event(event) == LoopControl::Quit {
+            break;
+        }
+    }
+
+    event_loop.on_shutdown();
+}
+
+/// Clamp the `size` of the field (`[width, height]`) to the range from `minimum` to `maximum` in each dimension.
+fn clamp_field_size(size: [u32; 2], minimum: [u32; 2], maximum: [u32; 2]) -> [u32; 2] {
+    [size[0].clamp(minimum[0], maximum[0]), size[1].clamp(minimum[1], maximum[1])]
+}
+
+/// Get the play area within a window of the given `window_size` (`[width, height]`): `[x, y, width, height]`. If an
+/// `aspect_ratio` (width to height) is given, the play area is the largest area of this ratio centered within the
+/// window. Otherwise, it covers the entire window.
+fn play_area(window_size: [u32; 2], aspect_ratio: Option<f64>) -> [u32; 4] {
+    let [width, height]: [u32; 2] = window_size;
+    let ratio: f64 = match aspect_ratio {
+        Some(ratio) => ratio,
+        None => return [0, 0, width, height],
+    };
+
+    if f64::from(width) > f64::from(height) * ratio {
+        // The window is too wide: add bars on the left and the right.
+        let area_width: u32 = (f64::from(height) * ratio).round() as u32;
+        [(width - area_width) / 2, 0, area_width, height]
+    }
+    else {
+        // The window is too high: add bars at the top and the bottom.
+        let area_height: u32 = (f64::from(width) / ratio).round() as u32;
+        [0, (height - area_height) / 2, width, area_height]
+    }
+}
+
+/// Get the vertical offsets of the field and the scoreboard, respectively, for a field of the given `field_height`,
+/// with the scoreboard at the given `position`.
+fn vertical_offsets(field_height: u32, position: ScoreboardPosition) -> (f64, f64) {
+    match position {
+        ScoreboardPosition::Bottom => (0.0, f64::from(field_height)),
+        ScoreboardPosition::Top => (f64::from(SCOREBOARD_HEIGHT), 0.0),
+    }
+}
+
+/// Find the folder containing the assets.
+///
+/// If an `override_path` is given (e.g. from an environment variable), it is used directly if it is an existing folder,
+/// otherwise, an error is returned. Without an `override_path`, the folder is searched in the parent and child folders
+/// of the working directory.
+fn find_assets(override_path: Option<OsString>) -> Result<PathBuf> {
+    match override_path {
+        Some(path) => {
+            let path = PathBuf::from(path);
+            if path.is_dir() {
+                Ok(path)
+            }
+            else {
+                Err(Error::IO(FindFolderError::NotFound))
+            }
+        },
+        None => Ok(Search::ParentsThenKids(3, 1).for_folder("assets")?),
+    }
+}
+
+/// Check if the game can be started in the current environment: the settings are valid, the assets folder can be found,
+/// the font can be loaded, and an OpenGL context can be created. The outcome of each check is printed.
+///
+/// Returns the error of the first failed check.
+pub fn check_environment() -> Result<()> {
+    /// Print the outcome of the check with the given `name` and pass on its `result`.
+    fn report<T>(name: &str, result: Result<T>) -> Result<T> {
+        match result {
+            Ok(_) => println!("[pass] {name}", name = name),
+            Err(ref error) => println!("[fail] {name}: {error}", name = name, error = error),
+        }
+        result
+    }
+
+    let config: Config = report("Settings", load_config())?;
+    let assets: PathBuf = report("Assets folder", find_assets(env::var_os(ASSETS_VARIABLE)))?;
+    report("Font", check_font(&assets.join(FONT)))?;
+    let _ = report("OpenGL context", create_window(&config))?;
+    Ok(())
+}
+
+/// Run the game without a window for the given number of `frames`, customized like the game itself, and write the
+/// state of each frame as CSV to `writer`.
+///
+/// Returns an error if the settings are invalid or the CSV cannot be written.
+pub fn dump_game<W: Write>(frames: usize, writer: &mut W) -> Result<()> {
+    let config: Config = load_config()?;
+    let [width, height]: [u32; 2] = WINDOW_SIZE;
+    let mut field = Field::new([width, height - SCOREBOARD_HEIGHT], &config);
+    write_frames(&mut field, frames, DUMP_FRAME_TIME, writer)
+}
+
+/// Simulate `frames` steps of `dt` seconds each on the `field` and write the state after each step as CSV to
+/// `writer`, preceded by a header: the frame, the simulated time, the ball's center and speed, and the scores.
+///
+/// Returns an error if the CSV cannot be written.
+fn write_frames<W: Write>(field: &mut Field, frames: usize, dt: f64, writer: &mut W) -> Result<()> {
+    writeln!(writer, "frame,time,ball_x,ball_y,speed_x,speed_y,score0,score1")?;
+    for frame in 1..=frames {
+        let _ = field.step(dt);
+        let (ball_x, ball_y): (f64, f64) = field.ball_center();
+        let (speed_x, speed_y): (f64, f64) = field.ball_speed();
+        let [score_0, score_1]: [isize; 2] = field.get_player_scores();
+        writeln!(writer, "{},{},{},{},{},{},{},{}",
+                 frame, field.elapsed_seconds(), ball_x, ball_y, speed_x, speed_y, score_0, score_1)?;
+    }
+    Ok(())
+}
+
+/// Load the settings, customized by the environment variables and the command line arguments.
+///
+/// Returns an error if any setting is invalid.
+fn load_config() -> Result<Config> {
+    let mut config = Config::default();
+    if let Ok(shrink) = env::var(BALL_SHRINK_VARIABLE) {
+        config.ball_shrink = Some(config::parse_ball_shrink(&shrink)?);
+    }
+    if let Ok(model) = env::var(BOUNCE_MODEL_VARIABLE) {
+        config.bounce_model = config::parse_bounce_model(&model)?;
+    }
+    if let Ok(preset) = env::var(CONTROLS_PRESET_VARIABLE) {
+        config.controls_preset = controls::parse_preset(&preset)?;
+    }
+    if let Ok(mode) = env::var(DIFFICULTY_VARIABLE) {
+        config.difficulty_mode = config::parse_difficulty_mode(&mode)?;
+    }
+    if let Ok(version) = env::var(OPENGL_VARIABLE) {
+        config.opengl = config::parse_opengl(&version)?;
+    }
+    if let Ok(level) = env::var(OPPONENT_VARIABLE) {
+        config.input_sources[1] = InputSource::Ai(config::parse_ai_level(&level)?);
+    }
+    if let Ok(cap) = env::var(RALLY_CAP_VARIABLE) {
+        config.rally_cap = Some(config::parse_rally_cap(&cap)?);
+    }
+    if let Ok(position) = env::var(SCOREBOARD_POSITION_VARIABLE) {
+        config.scoreboard_position = config::parse_scoreboard_position(&position)?;
+    }
+    if let Ok(score) = env::var(TARGET_SCORE_VARIABLE) {
+        config.target_score = Some(config::parse_target_score(&score)?);
+    }
+    config.seed = find_seed(env::args().skip(1))?;
+    config.validate().map_err(|problems| Error::Config(problems.join(" ")))?;
+    Ok(config)
+}
+
+/// Create the application window, rendering with the OpenGL version given by `config`.
+///
+/// Returns an error if the window or its OpenGL context cannot be created.
+fn create_window(config: &Config) -> Result<PistonWindow> {
+    WindowSettings::new(TITLE, WINDOW_SIZE)
+        .exit_on_esc(true)
+        .opengl(config.opengl)
+        .resizable(false)  // Not yet working - see https://github.com/PistonDevelopers/piston_window/issues/160.
+        .vsync(true)
+        .build()
+        .map_err(explain_window_error)
+}
+
+/// Check if the file at `path` can be read and is a TrueType or OpenType font.
+///
+/// Returns an error if the file cannot be read or is not a font.
+fn check_font(path: &Path) -> Result<()> {
+    /// The tags at the beginning of TrueType and OpenType font files.
+    const FONT_TAGS: [&[u8]; 4] = [b"\x00\x01\x00\x00", b"true", b"OTTO", b"ttcf"];
+
+    let mut tag: [u8; 4] = [0; 4];
+    File::open(path)?.read_exact(&mut tag)?;
+    if FONT_TAGS.contains(&&tag[..]) {
+        Ok(())
+    }
+    else {
+        let message: String = format!("{} is not a TrueType or OpenType font", path.display());
+        Err(Error::File(io::Error::new(io::ErrorKind::InvalidData, message)))
+    }
+}
+
+/// Find the seed for the game's random choices in the command line `arguments` (without the program's name), given as
+/// `--seed 42` or `--seed=42`.
+///
+/// Returns `None` if no seed is given, or an error if the seed is missing or invalid.
+fn find_seed<I: IntoIterator<Item = String>>(arguments: I) -> Result<Option<usize>> {
+    let mut arguments = arguments.into_iter();
+    let prefix: String = format!("{}=", SEED_ARGUMENT);
+    while let Some(argument) = arguments.next() {
+        if argument == SEED_ARGUMENT {
+            return match arguments.next() {
+                Some(seed) => Ok(Some(config::parse_seed(&seed)?)),
+                None => Err(Error::Config(format!("Missing value for \"{}\".", SEED_ARGUMENT))),
+            };
+        }
+        if argument.starts_with(&prefix) {
+            return Ok(Some(config::parse_seed(&argument[prefix.len()..])?));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Find the number of frames to dump in the command line `arguments` (without the program's name), given as
+/// `--dump 600` or `--dump=600`.
+///
+/// Returns `None` if the game is not to be dumped, or an error if the number of frames is missing or invalid.
+pub fn find_dump_frames<I: IntoIterator<Item = String>>(arguments: I) -> Result<Option<usize>> {
+    let parse = |frames: &str| -> Result<usize> {
+        frames.trim().parse().map_err(|_| Error::Config(format!("Invalid number of frames \"{}\".", frames)))
+    };
+
+    let mut arguments = arguments.into_iter();
+    let prefix: String = format!("{}=", DUMP_ARGUMENT);
+    while let Some(argument) = arguments.next() {
+        if argument == DUMP_ARGUMENT {
+            return match arguments.next() {
+                Some(frames) => Ok(Some(parse(&frames)?)),
+                None => Err(Error::Config(format!("Missing value for \"{}\".", DUMP_ARGUMENT))),
             };
         }
+        if argument.starts_with(&prefix) {
+            return Ok(Some(parse(&argument[prefix.len()..])?));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Add guidance on how to fix common causes to the error `message` of a failed window creation. The original message
+/// is kept at the end of the new message.
+fn explain_window_error(message: String) -> Error {
+    /// Parts of known error messages (in lowercase and without spaces) and the corresponding guidance.
+    const GUIDANCE: [(&str, &str); 3] = [
+        ("opengl", "Could not create an OpenGL context. Please update your graphics drivers."),
+        ("pixelformat", "Could not find a suitable pixel format. Please update your graphics drivers."),
+        ("display", "Could not connect to a display. Please make sure you are running a graphical session."),
+    ];
+
+    let normalized_message: String = message.to_lowercase().replace(' ', "");
+    match GUIDANCE.iter().find(|&&(part, _)| normalized_message.contains(part)) {
+        Some(&(_, guidance)) => {
+            Error::Piston(format!("{guidance} ({original})", guidance = guidance, original = message))
+        },
+        None => Error::Piston(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn outcome_from_stats() {
+        assert_eq!(Outcome::from_stats(&MatchStats::default()), Outcome::Abandoned);
+        assert_eq!(Outcome::from_stats(&MatchStats { points: [1, 0], ..MatchStats::default() }), Outcome::Quit);
+        assert_eq!(Outcome::from_stats(&MatchStats { points: [0, 2], ..MatchStats::default() }), Outcome::Quit);
+    }
+
+    #[test]
+    fn outcome_into_code() {
+        assert_eq!(Code::from(Outcome::Abandoned), Code::Abandoned);
+        assert_eq!(Code::from(Outcome::Quit), Code::Success);
+    }
+
+    /// An event loop processing a fixed list of events, recording the handled events and shutdowns.
+    #[derive(Debug, Default)]
+    struct RecordingLoop {
+        /// The events not yet processed, in reverse order. The event `0` requests to quit.
+        events: Vec<u8>,
+
+        /// The handled events.
+        handled: Vec<u8>,
+
+        /// The number of shutdowns.
+        shutdowns: usize,
+    }
+
+    impl EventLoop for RecordingLoop {
+        type Event = u8;
+
+        fn next_event(&mut self) -> Option<u8> {
+            self.events.pop()
+        }
+
+        fn handle_event(&mut self, event: u8) -> LoopControl {
+            self.handled.push(event);
+            if event == 0 { LoopControl::Quit } else { LoopControl::Continue }
+        }
+
+        fn on_shutdown(&mut self) {
+            self.shutdowns += 1;
+        }
+    }
+
+    #[test]
+    fn run_event_loop_until_no_events() {
+        let mut event_loop = RecordingLoop { events: vec![3, 2, 1], ..RecordingLoop::default() };
+        run_event_loop(&mut event_loop);
+        assert_eq!(event_loop.handled, vec![1, 2, 3]);
+        assert_eq!(event_loop.shutdowns, 1);
+    }
+
+    #[test]
+    fn run_event_loop_until_quit() {
+        let mut event_loop = RecordingLoop { events: vec![3, 0, 2, 1], ..RecordingLoop::default() };
+        run_event_loop(&mut event_loop);
+        assert_eq!(event_loop.handled, vec![1, 2, 0]);
+        assert_eq!(event_loop.events, vec![3]);
+        assert_eq!(event_loop.shutdowns, 1);
+    }
+
+    #[test]
+    fn explain_window_error_known() {
+        let error = explain_window_error(String::from("Couldn't find any pixel format that matches the criteria."));
+        assert_eq!(format!("{}", error), "Could not find a suitable pixel format. Please update your graphics \
+                                          drivers. (Couldn't find any pixel format that matches the criteria.)");
+
+        let error = explain_window_error(String::from("NoAvailablePixelFormat"));
+        assert!(format!("{}", error).starts_with("Could not find a suitable pixel format."));
+
+        let error = explain_window_error(String::from("OpenGL 3.2 is not supported"));
+        assert_eq!(format!("{}", error), "Could not create an OpenGL context. Please update your graphics drivers. \
+                                          (OpenGL 3.2 is not supported)");
+
+        let error = explain_window_error(String::from("Failed to open display"));
+        assert!(format!("{}", error).starts_with("Could not connect to a display."));
+    }
+
+    #[test]
+    fn explain_window_error_unknown() {
+        let message: &str = "Something unexpected happened";
+        let error = explain_window_error(String::from(message));
+        let mut is_piston_error: bool = false;
+        if let Error::Piston(ref description) = error {
+            is_piston_error = description == message;
+        }
+        assert!(is_piston_error, "Expected the original Piston failure.");
+    }
+
+    /// Convert the given command line `arguments` into owned strings.
+    fn arguments(arguments: &[&str]) -> Vec<String> {
+        arguments.iter().map(|argument| String::from(*argument)).collect()
+    }
+
+    #[test]
+    fn find_seed_given() {
+        assert_eq!(find_seed(arguments(&["--seed", "42"])).unwrap(), Some(42));
+        assert_eq!(find_seed(arguments(&["--other", "--seed=1337"])).unwrap(), Some(1337));
+    }
+
+    #[test]
+    fn find_seed_missing() {
+        assert_eq!(find_seed(arguments(&[])).unwrap(), None);
+        assert_eq!(find_seed(arguments(&["--other", "42"])).unwrap(), None);
+    }
+
+    #[test]
+    fn find_seed_invalid() {
+        assert!(find_seed(arguments(&["--seed"])).is_err());
+        assert!(find_seed(arguments(&["--seed", "many"])).is_err());
+        assert!(find_seed(arguments(&["--seed="])).is_err());
+    }
+
+    #[test]
+    fn find_dump_frames_given() {
+        assert_eq!(find_dump_frames(arguments(&["--dump", "600"])).unwrap(), Some(600));
+        assert_eq!(find_dump_frames(arguments(&["--seed", "42", "--dump=10"])).unwrap(), Some(10));
+    }
+
+    #[test]
+    fn find_dump_frames_missing() {
+        assert_eq!(find_dump_frames(arguments(&[])).unwrap(), None);
+        assert_eq!(find_dump_frames(arguments(&["--seed", "42"])).unwrap(), None);
+    }
+
+    #[test]
+    fn find_dump_frames_invalid() {
+        assert!(find_dump_frames(arguments(&["--dump"])).is_err());
+        assert!(find_dump_frames(arguments(&["--dump", "-1"])).is_err());
+        assert!(find_dump_frames(arguments(&["--dump="])).is_err());
+    }
+
+    #[test]
+    fn write_frames_rows() {
+        let config = Config { seed: Some(42), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let mut csv: Vec<u8> = Vec::new();
+        write_frames(&mut field, 10, 0.01, &mut csv).unwrap();
+
+        let csv: String = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "frame,time,ball_x,ball_y,speed_x,speed_y,score0,score1");
+        assert!(lines[1].starts_with("1,0.01,"));
+        assert!(lines[10].starts_with("10,"));
+        assert!(lines[1..].iter().all(|line| line.split(',').count() == 8));
+    }
+
+    #[test]
+    fn write_frames_reproducible() {
+        let config = Config { seed: Some(42), ..Config::default() };
+        let mut dumps: Vec<Vec<u8>> = vec![Vec::new(), Vec::new()];
+        for dump in &mut dumps {
+            let mut field = Field::new([200, 100], &config);
+            write_frames(&mut field, 100, 0.01, dump).unwrap();
+        }
+        assert_eq!(dumps[0], dumps[1]);
+    }
+
+    #[test]
+    fn clamp_field_size_within_bounds() {
+        assert_eq!(clamp_field_size([800, 560], [100, 50], [1000, 1000]), [800, 560]);
+        assert_eq!(clamp_field_size([100, 1000], [100, 50], [1000, 1000]), [100, 1000]);
+    }
+
+    #[test]
+    fn clamp_field_size_outside_bounds() {
+        assert_eq!(clamp_field_size([50, 560], [100, 50], [1000, 1000]), [100, 560]);
+        assert_eq!(clamp_field_size([800, 0], [100, 50], [1000, 1000]), [800, 50]);
+        assert_eq!(clamp_field_size([5000, 5000], [100, 50], [1000, 1000]), [1000, 1000]);
+    }
+
+    #[test]
+    fn play_area_without_aspect_ratio() {
+        assert_eq!(play_area([800, 600], None), [0, 0, 800, 600]);
+        assert_eq!(play_area([1000, 300], None), [0, 0, 1000, 300]);
+    }
+
+    #[test]
+    fn play_area_matching_window() {
+        assert_eq!(play_area([800, 600], Some(4.0 / 3.0)), [0, 0, 800, 600]);
+    }
+
+    #[test]
+    fn play_area_wide_window() {
+        assert_eq!(play_area([1000, 600], Some(4.0 / 3.0)), [100, 0, 800, 600]);
+        assert_eq!(play_area([1920, 1080], Some(4.0 / 3.0)), [240, 0, 1440, 1080]);
+    }
+
+    #[test]
+    fn play_area_high_window() {
+        assert_eq!(play_area([800, 800], Some(4.0 / 3.0)), [0, 100, 800, 600]);
+        assert_eq!(play_area([600, 1000], Some(2.0)), [0, 350, 600, 300]);
+    }
+
+    #[test]
+    fn vertical_offsets_top() {
+        assert_eq!(vertical_offsets(480, ScoreboardPosition::Top), (120.0, 0.0));
+        assert_eq!(vertical_offsets(0, ScoreboardPosition::Top), (120.0, 0.0));
+    }
+
+    #[test]
+    fn vertical_offsets_bottom() {
+        assert_eq!(vertical_offsets(480, ScoreboardPosition::Bottom), (0.0, 480.0));
+        assert_eq!(vertical_offsets(0, ScoreboardPosition::Bottom), (0.0, 0.0));
+
+        // If the field is limited to a smaller size, the scoreboard stays directly below it.
+        assert_eq!(vertical_offsets(300, ScoreboardPosition::Bottom), (0.0, 300.0));
+    }
+
+    #[test]
+    fn find_assets_override() {
+        let folder: PathBuf = env::temp_dir();
+        let assets = find_assets(Some(folder.clone().into_os_string()));
+        assert_eq!(assets.unwrap(), folder);
+    }
+
+    #[test]
+    fn find_assets_override_missing() {
+        let folder: PathBuf = env::temp_dir().join("mief-missing-assets");
+        let mut is_io_error: bool = false;
+        if let Err(Error::IO(_)) = find_assets(Some(folder.into_os_string())) {
+            is_io_error = true;
+        }
+        assert!(is_io_error, "Expected IO failure.");
+    }
+
+    #[test]
+    fn check_font_valid() {
+        let assets: PathBuf = find_assets(None).unwrap();
+        assert!(check_font(&assets.join(FONT)).is_ok());
+    }
+
+    #[test]
+    fn check_font_missing() {
+        let path: PathBuf = env::temp_dir().join("mief-missing-font.ttf");
+        let mut is_file_error: bool = false;
+        if let Err(Error::File(_)) = check_font(&path) {
+            is_file_error = true;
+        }
+        assert!(is_file_error, "Expected file failure.");
+    }
+
+    #[test]
+    fn check_font_invalid() {
+        let assets: PathBuf = find_assets(None).unwrap();
+        let mut is_file_error: bool = false;
+        if let Err(Error::File(_)) = check_font(&assets.join(CONTROLS)) {
+            is_file_error = true;
+        }
+        assert!(is_file_error, "Expected file failure.");
+    }
+
+    #[test]
+    fn find_assets_search() {
+        let assets: PathBuf = find_assets(None).unwrap();
+        assert!(assets.ends_with("assets"));
+        assert!(assets.join(CONTROLS).is_file());
     }
 }
@@ -0,0 +1,95 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! A camera zooming in on the field and following the ball.
+
+use config::CameraFollow;
+
+/// A camera showing a zoomed-in part of the field, easing towards the point it follows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Camera {
+    /// The point of the field (`(x, y)`) currently shown at the center of the view.
+    focus: (f64, f64),
+
+    /// The rate at which the camera eases towards the point it follows, per second.
+    smoothing: f64,
+
+    /// The factor by which the field is magnified.
+    zoom: f64,
+}
+
+impl Camera {
+    /// Create a new camera with the given `settings`, initially showing `focus` at the center of the view.
+    pub fn new(settings: CameraFollow, focus: (f64, f64)) -> Camera {
+        Camera {
+            focus,
+            smoothing: settings.smoothing,
+            zoom: settings.zoom,
+        }
+    }
+
+    /// Get the transformation of the field for a view of the given size (`[width, height]`): the translation
+    /// `(x, y)`, applied after magnifying the field by the returned zoom factor.
+    pub fn transform(&self, view_size: [f64; 2]) -> ((f64, f64), f64) {
+        camera_transform(self.focus, view_size, self.zoom)
+    }
+
+    /// Ease the camera towards the `target` by the change in time `dt`.
+    pub fn update(&mut self, target: (f64, f64), dt: f64) {
+        // Close the same fraction of the remaining distance per time, independent of the frame rate.
+        let fraction: f64 = 1.0 - (-self.smoothing * dt).exp();
+        self.focus.0 += (target.0 - self.focus.0) * fraction;
+        self.focus.1 += (target.1 - self.focus.1) * fraction;
+    }
+}
+
+/// Get the transformation showing `focus` at the center of a view with the given size (`[width, height]`) when
+/// magnified by `zoom`: the translation `(x, y)` applied after magnifying, and the zoom factor.
+pub fn camera_transform(focus: (f64, f64), view_size: [f64; 2], zoom: f64) -> ((f64, f64), f64) {
+    let translation: (f64, f64) = (view_size[0] / 2.0 - focus.0 * zoom, view_size[1] / 2.0 - focus.1 * zoom);
+    (translation, zoom)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn camera_transform_centers_focus() {
+        let focus: (f64, f64) = (30.0, 70.0);
+        let ((x, y), zoom): ((f64, f64), f64) = camera_transform(focus, [200.0, 100.0], 2.0);
+        assert_eq!(zoom, 2.0);
+
+        // The focused point is mapped onto the center of the view.
+        assert_eq!((focus.0 * zoom + x, focus.1 * zoom + y), (100.0, 50.0));
+    }
+
+    #[test]
+    fn camera_transform_without_zoom() {
+        assert_eq!(camera_transform((100.0, 50.0), [200.0, 100.0], 1.0), ((0.0, 0.0), 1.0));
+    }
+
+    #[test]
+    fn update_eases() {
+        let mut camera = Camera::new(CameraFollow { smoothing: 5.0, zoom: 2.0 }, (0.0, 0.0));
+        camera.update((100.0, -100.0), 0.1);
+        assert!(camera.focus.0 > 0.0 && camera.focus.0 < 100.0);
+        assert!(camera.focus.1 < 0.0 && camera.focus.1 > -100.0);
+        assert_eq!(camera.focus.0, -camera.focus.1);
+
+        // Eventually, the camera reaches its target.
+        camera.update((100.0, -100.0), 10.0);
+        assert!((camera.focus.0 - 100.0).abs() < 1e-9);
+        assert!((camera.focus.1 + 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn update_without_time() {
+        let mut camera = Camera::new(CameraFollow { smoothing: 5.0, zoom: 2.0 }, (10.0, 20.0));
+        camera.update((100.0, 100.0), 0.0);
+        assert_eq!(camera.transform([20.0, 40.0]), ((-10.0, -20.0), 2.0));
+    }
+}
@@ -16,5 +16,129 @@ pub const GRAY: [f32; 4] = [0.5, 0.5, 0.5, 1.0];
 #[cfg(feature = "display-fps")]
 pub const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
 
+/// `#ff0000`, `100%` opacity.
+pub const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
 /// `#ffffff`, `100%` opacity.
 pub const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+/// Convert a color given by its `hue` (in degrees), `saturation`, and `value` (both within `[0, 1]`) to RGB with
+/// `100%` opacity. Hues outside of `[0, 360)` are wrapped around the hue wheel.
+pub fn from_hsv(hue: f64, saturation: f64, value: f64) -> [f32; 4] {
+    let hue: f64 = ((hue % 360.0) + 360.0) % 360.0;
+    let chroma: f64 = value * saturation;
+    let x: f64 = chroma * (1.0 - ((hue / 60.0) % 2.0 - 1.0).abs());
+    let minimum: f64 = value - chroma;
+
+    let (red, green, blue): (f64, f64, f64) = match (hue / 60.0) as u8 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+
+    [(red + minimum) as f32, (green + minimum) as f32, (blue + minimum) as f32, 1.0]
+}
+
+/// Darken the `color` by the given `amount` within `[0, 1]`, mixing it with black: `0` keeps the color, `1` turns it
+/// black. The opacity is kept, and all components are clamped to `[0, 1]`.
+#[allow(dead_code)]  // Not used until themes are implemented.
+pub fn darken(color: [f32; 4], amount: f32) -> [f32; 4] {
+    let factor: f32 = 1.0 - amount.clamp(0.0, 1.0);
+    [
+        (color[0] * factor).clamp(0.0, 1.0),
+        (color[1] * factor).clamp(0.0, 1.0),
+        (color[2] * factor).clamp(0.0, 1.0),
+        color[3].clamp(0.0, 1.0),
+    ]
+}
+
+/// Lighten the `color` by the given `amount` within `[0, 1]`, mixing it with white: `0` keeps the color, `1` turns it
+/// white. The opacity is kept, and all components are clamped to `[0, 1]`.
+#[allow(dead_code)]  // Not used until themes are implemented.
+pub fn lighten(color: [f32; 4], amount: f32) -> [f32; 4] {
+    let amount: f32 = amount.clamp(0.0, 1.0);
+    let mix = |component: f32| -> f32 { (component + (1.0 - component) * amount).clamp(0.0, 1.0) };
+    [mix(color[0]), mix(color[1]), mix(color[2]), color[3].clamp(0.0, 1.0)]
+}
+
+/// Replace the opacity of the `color` with `alpha`, clamped to `[0, 1]`.
+pub fn with_alpha(color: [f32; 4], alpha: f32) -> [f32; 4] {
+    [color[0], color[1], color[2], alpha.clamp(0.0, 1.0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_hsv_primary_colors() {
+        assert_eq!(from_hsv(0.0, 1.0, 1.0), RED);
+        assert_eq!(from_hsv(120.0, 1.0, 1.0), [0.0, 1.0, 0.0, 1.0]);
+        assert_eq!(from_hsv(240.0, 1.0, 1.0), [0.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn from_hsv_mixed_colors() {
+        assert_eq!(from_hsv(60.0, 1.0, 1.0), [1.0, 1.0, 0.0, 1.0]);
+        assert_eq!(from_hsv(180.0, 1.0, 1.0), [0.0, 1.0, 1.0, 1.0]);
+        assert_eq!(from_hsv(300.0, 1.0, 1.0), [1.0, 0.0, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn from_hsv_saturation_and_value() {
+        assert_eq!(from_hsv(0.0, 0.0, 1.0), WHITE);
+        assert_eq!(from_hsv(0.0, 0.0, 0.5), GRAY);
+        assert_eq!(from_hsv(240.0, 1.0, 0.0), BLACK);
+    }
+
+    #[test]
+    fn from_hsv_wraps() {
+        assert_eq!(from_hsv(360.0, 1.0, 1.0), from_hsv(0.0, 1.0, 1.0));
+        assert_eq!(from_hsv(480.0, 1.0, 1.0), from_hsv(120.0, 1.0, 1.0));
+        assert_eq!(from_hsv(-120.0, 1.0, 1.0), from_hsv(240.0, 1.0, 1.0));
+    }
+
+    #[test]
+    fn darken_amounts() {
+        assert_eq!(darken([0.8, 0.4, 0.2, 0.5], 0.0), [0.8, 0.4, 0.2, 0.5]);
+        assert_eq!(darken([0.8, 0.4, 0.2, 0.5], 0.5), [0.4, 0.2, 0.1, 0.5]);
+        assert_eq!(darken([0.8, 0.4, 0.2, 0.5], 1.0), [0.0, 0.0, 0.0, 0.5]);
+    }
+
+    #[test]
+    fn darken_clamps() {
+        assert_eq!(darken(WHITE, -1.0), WHITE);
+        assert_eq!(darken(WHITE, 2.0), BLACK);
+        assert_eq!(darken([1.5, -0.5, 0.5, 2.0], 0.0), [1.0, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn lighten_amounts() {
+        assert_eq!(lighten([0.0, 0.5, 1.0, 0.5], 0.0), [0.0, 0.5, 1.0, 0.5]);
+        assert_eq!(lighten([0.0, 0.5, 1.0, 0.5], 0.5), [0.5, 0.75, 1.0, 0.5]);
+        assert_eq!(lighten([0.0, 0.5, 1.0, 0.5], 1.0), [1.0, 1.0, 1.0, 0.5]);
+    }
+
+    #[test]
+    fn lighten_clamps() {
+        assert_eq!(lighten(BLACK, -1.0), BLACK);
+        assert_eq!(lighten(BLACK, 2.0), WHITE);
+        assert_eq!(lighten([1.5, -0.5, 0.5, 2.0], 0.0), [1.0, 0.0, 0.5, 1.0]);
+    }
+
+    #[test]
+    fn with_alpha_values() {
+        assert_eq!(with_alpha(RED, 0.0), [1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(with_alpha(RED, 0.5), [1.0, 0.0, 0.0, 0.5]);
+        assert_eq!(with_alpha([1.0, 0.0, 0.0, 0.5], 1.0), RED);
+    }
+
+    #[test]
+    fn with_alpha_clamps() {
+        assert_eq!(with_alpha(RED, -0.5), [1.0, 0.0, 0.0, 0.0]);
+        assert_eq!(with_alpha(RED, 1.5), RED);
+    }
+}
@@ -0,0 +1,1059 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Settings to customize the game.
+
+use piston_window::OpenGL;
+
+use color;
+use controls::ControlsPreset;
+use execution_flow::Error;
+use execution_flow::Result;
+
+/// The default size of each player's handle: `(width, height)`.
+pub const DEFAULT_HANDLE_SIZE: (f64, f64) = (10.0, 60.0);
+
+/// The maximum height of a player's handle.
+pub const MAXIMUM_HANDLE_HEIGHT: f64 = 120.0;
+
+/// The minimum height of a player's handle.
+pub const MINIMUM_HANDLE_HEIGHT: f64 = 20.0;
+
+/// The OpenGL versions that can be selected.
+const OPENGL_VERSIONS: [OpenGL; 12] = [
+    OpenGL::V2_0, OpenGL::V2_1,
+    OpenGL::V3_0, OpenGL::V3_1, OpenGL::V3_2, OpenGL::V3_3,
+    OpenGL::V4_0, OpenGL::V4_1, OpenGL::V4_2, OpenGL::V4_3, OpenGL::V4_4, OpenGL::V4_5,
+];
+
+/// The skill of a player controlled by the computer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AiLevel {
+    /// Only follow the ball while it approaches, allowing for some inaccuracy.
+    Easy,
+
+    /// Always follow the ball closely.
+    Hard,
+}
+
+/// The ways the ball shrinks as it speeds up.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BallShrink {
+    /// The ball shrinks both when drawn and when colliding with walls and handles.
+    Collision,
+
+    /// Only the drawn ball shrinks. It still collides with walls and handles at its full size.
+    Drawn,
+}
+
+/// The ways the ball bounces off the sides of a handle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BounceModel {
+    /// The ball leaves the handle at an angle depending on where it hit the handle: the farther from the handle's
+    /// center, the steeper the angle. Hits on the handle's center send the ball off horizontally.
+    Angled,
+
+    /// The ball is reflected, keeping the angle at which it hit the handle.
+    Reflect,
+
+    /// Like in the original Pong, the handle is divided into the given number of segments of equal height, each
+    /// sending the ball off at a fixed angle: the farther from the handle's center, the steeper the angle.
+    Segmented(usize),
+}
+
+#[allow(clippy::derivable_impls)]  // Deriving the default variant needs Rust 1.62.
+impl Default for BounceModel {
+    fn default() -> BounceModel {
+        BounceModel::Reflect
+    }
+}
+
+/// The ways the game becomes more difficult during a rally.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DifficultyMode {
+    /// The ball and the handles speed up in regular intervals.
+    Ramp,
+
+    /// The ball only speeds up when it is returned by a handle. After each point, it starts at its base speed again.
+    Skill,
+}
+
+#[allow(clippy::derivable_impls)]  // Deriving the default variant needs Rust 1.62.
+impl Default for DifficultyMode {
+    fn default() -> DifficultyMode {
+        DifficultyMode::Ramp
+    }
+}
+
+/// The sources controlling the movement of a player's handle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InputSource {
+    /// The computer moves the handle towards the ball at the given skill level.
+    Ai(AiLevel),
+
+    /// A human moves the handle with the keyboard.
+    Keyboard,
+}
+
+/// The limits of a rally's length after which a let is declared: no one scores and a new ball is served.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RallyCap {
+    /// The maximum number of times the ball hits a handle.
+    Hits(usize),
+
+    /// The maximum duration of a rally (in seconds), starting when the ball is served.
+    Seconds(f64),
+}
+
+/// The positions of the scoreboard in the window.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScoreboardPosition {
+    /// Below the field.
+    Bottom,
+
+    /// Above the field.
+    Top,
+}
+
+/// The rules for scoring points when the ball leaves the field.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ScoringRule {
+    /// If a player's handle was the last one to touch the ball before it left the field on that player's side, the
+    /// player loses a point instead of the other player getting one.
+    OwnGoals,
+
+    /// The other side's player always gets a point.
+    Standard,
+}
+
+/// The zoom and the smoothing of a camera following the ball.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CameraFollow {
+    /// The rate at which the camera eases towards the ball, per second. Larger values follow the ball more closely.
+    pub smoothing: f64,
+
+    /// The factor by which the field is magnified.
+    pub zoom: f64,
+}
+
+/// The timing and strength of sudden speed bursts of the ball.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SpeedBurst {
+    /// The duration of a burst (in seconds).
+    pub duration: f64,
+
+    /// The time between two bursts (in seconds).
+    pub interval: f64,
+
+    /// The factor by which the ball's speed is multiplied during a burst.
+    pub multiplier: f64,
+}
+
+/// The settings customizing the game.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    /// The distance between the centers of the ball and a handle controlled by the computer within which the computer
+    /// keeps the handle still, as a fraction of the handle's height. If not set, the distance depends on the
+    /// computer's skill level.
+    pub ai_dead_zone: Option<f64>,
+
+    /// The time (in seconds) for which the computer keeps its decision how to move a handle before reconsidering it.
+    /// With `0`, the computer reconsiders its decision in every frame.
+    pub ai_decision_interval: f64,
+
+    /// Let the players aim each serve with the `Left` and `Right` keys while the ball waits to be served, showing a
+    /// preview of the ball's direction, e.g. for practicing.
+    pub aim_serves: bool,
+
+    /// If set, the field and the scoreboard keep this ratio of width to height when the window is resized, centered
+    /// within the window with black bars filling the remaining space.
+    pub aspect_ratio: Option<f64>,
+
+    /// If set, the ball shrinks as it speeds up, giving a visual cue of its speed and making it harder to return.
+    pub ball_shrink: Option<BallShrink>,
+
+    /// Draw a tail behind the ball showing the direction of its movement.
+    pub ball_tail: bool,
+
+    /// If set, the ball is only drawn while its center is within this horizontal distance of either handle, e.g. for an
+    /// extreme challenge.
+    pub ball_visibility_range: Option<f64>,
+
+    /// The color of the lines bordering the field.
+    pub border_color: [f32; 4],
+
+    /// The way the ball bounces off the sides of the handles.
+    pub bounce_model: BounceModel,
+
+    /// If set, the view zooms in on the field and follows the ball.
+    pub camera_follow: Option<CameraFollow>,
+
+    /// The color of the dashed line in the center of the field.
+    pub center_line_color: [f32; 4],
+
+    /// The default key bindings for the players, used for all bindings missing in the controls file.
+    pub controls_preset: ControlsPreset,
+
+    /// The way the game becomes more difficult during a rally.
+    pub difficulty_mode: DifficultyMode,
+
+    /// The time (in seconds) before the first ball of a match is served.
+    pub first_serve_delay: f64,
+
+    /// Shrink the leading player's handle and grow the trailing player's handle depending on the difference between
+    /// their scores.
+    pub handicap: bool,
+
+    /// The sizes of the left and the right player's handles: `(width, height)`, e.g. to give the weaker player a
+    /// larger handle.
+    pub handle_sizes: [(f64, f64); 2],
+
+    /// The sources controlling the left and the right player, respectively.
+    pub input_sources: [InputSource; 2],
+
+    /// Draw the scoreboard's text larger and bold for better readability.
+    pub large_text: bool,
+
+    /// Do not let the players move their handles while the ball waits to be served.
+    pub lock_paddles_on_serve: bool,
+
+    /// The largest size of the field (`[width, height]`) when the window is resized. Larger sizes are clamped.
+    pub max_field_size: [u32; 2],
+
+    /// The maximum number of digits of a score shown on the scoreboard. Larger scores are abbreviated, e.g. `1.2M`.
+    pub max_score_digits: usize,
+
+    /// The smallest size of the field (`[width, height]`) when the window is resized. Smaller sizes are clamped.
+    pub min_field_size: [u32; 2],
+
+    /// The minimum fraction of the ball's total speed that must be in the horizontal direction, preventing the ball
+    /// from bouncing (almost) vertically forever. Must be within `[0, 1]`.
+    pub min_horizontal_speed: f64,
+
+    /// The minimum number of times the ball must hit a handle in a rally before a point can be scored, e.g. for
+    /// warming up. If the ball leaves the field earlier, a new ball is served without anyone scoring.
+    pub min_rally_to_score: usize,
+
+    /// The OpenGL version used for rendering.
+    pub opengl: OpenGL,
+
+    /// Draw a fading after-image behind the players' handles when they move fast.
+    pub paddle_after_image: bool,
+
+    /// Let overlapping handles push each other apart instead of overlapping.
+    pub paddle_collision: bool,
+
+    /// Snap all drawn coordinates to whole device pixels for a crisp look, e.g. on high-DPI displays.
+    pub pixel_snapping: bool,
+
+    /// If set, the field briefly flashes in the color of the side that scored a point: `[left, right]`.
+    pub point_flash_colors: Option<[[f32; 4]; 2]>,
+
+    /// The number of times per game a wall behind the handle of each player controlled with the keyboard returns a
+    /// missed ball instead of conceding a point, e.g. for beginners.
+    pub practice_saves: usize,
+
+    /// Cycle the ball's color through the colors of the rainbow.
+    pub rainbow_ball: bool,
+
+    /// Move the players' handles back to the vertical center of the field whenever a new ball is served after a point.
+    pub recenter_paddles_on_point: bool,
+
+    /// The limit of a rally's length, if any. Once a rally reaches the limit, a let is declared: no one scores and a
+    /// new ball is served.
+    pub rally_cap: Option<RallyCap>,
+
+    /// The fraction of its speed the ball keeps when bouncing off a wall or a handle. Must be within `(0, 1]`.
+    pub restitution: f64,
+
+    /// The position of the scoreboard relative to the field.
+    pub scoreboard_position: ScoreboardPosition,
+
+    /// The rules for scoring points.
+    pub scoring_rule: ScoringRule,
+
+    /// The seed for all random choices of the game, e.g. the directions of new balls. The same seed and the same
+    /// input lead to the same game. If `None`, a random seed is used.
+    pub seed: Option<usize>,
+
+    /// If set, a new ball after a point is served towards the player who conceded the point, at a random angle to the
+    /// horizontal of at most this many degrees. Must be within `[0, 90)`.
+    pub serve_cone: Option<f64>,
+
+    /// The time (in seconds) before a new ball is served after a point has been scored.
+    pub serve_delay: f64,
+
+    /// If set, every ball is served with this total speed (in pixels per second), no matter how diagonal its direction
+    /// is. Otherwise, diagonal serves are faster than horizontal ones.
+    pub serve_speed: Option<f64>,
+
+    /// Mark the score of the player serving the ball while the ball waits to be served.
+    pub show_serving_side: bool,
+
+    /// Sudden speed bursts of the ball, if any.
+    pub speed_burst: Option<SpeedBurst>,
+
+    /// The scores of the left and the right player at the start of a match, e.g. to give the weaker player a head
+    /// start.
+    pub starting_scores: [isize; 2],
+
+    /// The score a player must reach with a lead of at least two points to win the match. If not set, the match never
+    /// ends.
+    pub target_score: Option<isize>,
+
+    /// Target zones for practicing on the edge of the field opposite the player controlled with the keyboard (the left
+    /// player, unless playing alone on the right side): `(top y, bottom y, bonus points)`. If the ball leaves the field
+    /// within a target zone, the player gets the bonus points in addition to the usual point.
+    pub targets: Vec<(f64, f64, isize)>,
+
+    /// The minimum time (in seconds of the game) between two actions of a toggle key, e.g. switching sides. Presses
+    /// of the key within this time after an action are ignored.
+    pub toggle_debounce: f64,
+
+    /// Let the ball bounce off the line drawn at the top of the field instead of the field's top edge.
+    pub top_line_collision: bool,
+
+    /// Let the ball leave the field on the top and reappear at the bottom (and vice versa) instead of bouncing off.
+    pub vertical_wrap: bool,
+}
+
+impl Config {
+    /// Check if all settings are valid.
+    ///
+    /// Returns descriptions of all invalid settings otherwise.
+    pub fn validate(&self) -> ::std::result::Result<(), Vec<String>> {
+        let mut problems: Vec<String> = Vec::new();
+
+        if let Some(dead_zone) = self.ai_dead_zone {
+            if !(dead_zone >= 0.0 && dead_zone.is_finite()) {
+                problems.push(format!("The computer's dead zone must not be negative, but is {}.", dead_zone));
+            }
+        }
+
+        if self.ai_decision_interval.is_nan() || self.ai_decision_interval < 0.0 {
+            problems.push(format!("The computer's decision interval must not be negative, but is {}.",
+                                  self.ai_decision_interval));
+        }
+
+        if let Some(ratio) = self.aspect_ratio {
+            if !(ratio > 0.0 && ratio.is_finite()) {
+                problems.push(format!("The aspect ratio must be positive, but is {}.", ratio));
+            }
+        }
+
+        if let Some(range) = self.ball_visibility_range {
+            if range.is_nan() || range < 0.0 {
+                problems.push(format!("The ball's visibility range must not be negative, but is {}.", range));
+            }
+        }
+
+        if self.bounce_model == BounceModel::Segmented(0) {
+            problems.push(String::from("The handles must be divided into at least one segment."));
+        }
+
+        if let Some(camera) = self.camera_follow {
+            let is_valid_camera: bool = camera.zoom >= 1.0 && camera.zoom.is_finite() &&
+                                        camera.smoothing > 0.0 && camera.smoothing.is_finite();
+            if !is_valid_camera {
+                problems.push(format!("The camera's zoom must be at least 1 and its smoothing must be positive, but \
+                                       are {} and {}.",
+                                      camera.zoom, camera.smoothing));
+            }
+        }
+
+        if self.first_serve_delay.is_nan() || self.first_serve_delay < 0.0 {
+            problems.push(format!("The first serve delay must not be negative, but is {}.", self.first_serve_delay));
+        }
+
+        for &(width, height) in &self.handle_sizes {
+            if !(width > 0.0 && width.is_finite()) {
+                problems.push(format!("The width of a handle must be positive, but is {}.", width));
+            }
+            if !(MINIMUM_HANDLE_HEIGHT..=MAXIMUM_HANDLE_HEIGHT).contains(&height) {
+                problems.push(format!("The height of a handle must be between {} and {}, but is {}.",
+                                      MINIMUM_HANDLE_HEIGHT, MAXIMUM_HANDLE_HEIGHT, height));
+            }
+        }
+
+        if self.min_field_size[0] > self.max_field_size[0] || self.min_field_size[1] > self.max_field_size[1] {
+            problems.push(format!("The minimum field size must not exceed the maximum field size, but is {}x{} and \
+                                   {}x{}, respectively.",
+                                  self.min_field_size[0], self.min_field_size[1],
+                                  self.max_field_size[0], self.max_field_size[1]));
+        }
+
+        if !(self.min_horizontal_speed >= 0.0 && self.min_horizontal_speed <= 1.0) {
+            problems.push(format!("The minimum horizontal speed must be within [0, 1], but is {}.",
+                                  self.min_horizontal_speed));
+        }
+
+        match self.rally_cap {
+            Some(RallyCap::Hits(0)) => problems.push(String::from("A rally must be capped at one or more hits.")),
+            Some(RallyCap::Seconds(seconds)) if seconds.is_nan() || seconds <= 0.0 => {
+                problems.push(format!("A rally must be capped at a positive duration, but is capped at {}.", seconds));
+            },
+            _ => {},
+        }
+
+        if !(self.restitution > 0.0 && self.restitution <= 1.0) {
+            problems.push(format!("The restitution must be within (0, 1], but is {}.", self.restitution));
+        }
+
+        if let Some(cone) = self.serve_cone {
+            if !(0.0..90.0).contains(&cone) {
+                problems.push(format!("The serve cone must be within [0, 90), but is {}.", cone));
+            }
+        }
+
+        if self.serve_delay.is_nan() || self.serve_delay < 0.0 {
+            problems.push(format!("The serve delay must not be negative, but is {}.", self.serve_delay));
+        }
+
+        if let Some(speed) = self.serve_speed {
+            if !(speed > 0.0 && speed.is_finite()) {
+                problems.push(format!("The serve speed must be positive, but is {}.", speed));
+            }
+        }
+
+        if let Some(burst) = self.speed_burst {
+            let is_valid_burst: bool = burst.interval > 0.0 && burst.duration > 0.0 && burst.multiplier > 0.0;
+            if !is_valid_burst {
+                problems.push(format!("The interval, duration, and multiplier of speed bursts must be positive, but \
+                                       are {}, {}, and {}.",
+                                      burst.interval, burst.duration, burst.multiplier));
+            }
+        }
+
+        if let Some(score) = self.target_score {
+            if score <= 0 {
+                problems.push(format!("The target score must be positive, but is {}.", score));
+            }
+            if self.starting_scores.iter().any(|&starting_score| starting_score >= score) {
+                problems.push(format!("The starting scores must be below the target score {}, but are {} and {}.",
+                                      score, self.starting_scores[0], self.starting_scores[1]));
+            }
+        }
+
+        if self.toggle_debounce.is_nan() || self.toggle_debounce < 0.0 {
+            problems.push(format!("The toggle debounce must not be negative, but is {}.", self.toggle_debounce));
+        }
+
+        if problems.is_empty() {
+            Ok(())
+        }
+        else {
+            Err(problems)
+        }
+    }
+}
+
+/// Parse an OpenGL version given as `major.minor`, e.g. `3.2`.
+///
+/// Returns an error if the version is not supported.
+pub fn parse_opengl(version: &str) -> Result<OpenGL> {
+    let version: &str = version.trim();
+    OPENGL_VERSIONS.iter()
+        .find(|opengl| {
+            let (major, minor): (isize, isize) = opengl.get_major_minor();
+            format!("{}.{}", major, minor) == version
+        })
+        .cloned()
+        .ok_or_else(|| Error::Config(format!("Unsupported OpenGL version \"{}\".", version)))
+}
+
+/// Parse the skill level of a player controlled by the computer: `easy` or `hard`.
+///
+/// Returns an error if there is no such level.
+pub fn parse_ai_level(level: &str) -> Result<AiLevel> {
+    match level.trim() {
+        "easy" => Ok(AiLevel::Easy),
+        "hard" => Ok(AiLevel::Hard),
+        level => Err(Error::Config(format!("Unknown computer opponent level \"{}\".", level))),
+    }
+}
+
+/// Parse the way the ball shrinks as it speeds up: `drawn` (only the drawn ball) or `collision` (also its size when
+/// colliding).
+///
+/// Returns an error if there is no such way.
+pub fn parse_ball_shrink(shrink: &str) -> Result<BallShrink> {
+    match shrink.trim() {
+        "collision" => Ok(BallShrink::Collision),
+        "drawn" => Ok(BallShrink::Drawn),
+        shrink => Err(Error::Config(format!("Unknown way of shrinking the ball \"{}\".", shrink))),
+    }
+}
+
+/// Parse the way the ball bounces off the sides of a handle: `reflect`, `angled`, or `segmented` (with eight segments
+/// like in the original Pong).
+///
+/// Returns an error if there is no such way.
+pub fn parse_bounce_model(model: &str) -> Result<BounceModel> {
+    match model.trim() {
+        "angled" => Ok(BounceModel::Angled),
+        "reflect" => Ok(BounceModel::Reflect),
+        "segmented" => Ok(BounceModel::Segmented(8)),
+        model => Err(Error::Config(format!("Unknown bounce model \"{}\".", model))),
+    }
+}
+
+/// Parse the way the game becomes more difficult: `ramp` or `skill`.
+///
+/// Returns an error if there is no such mode.
+pub fn parse_difficulty_mode(mode: &str) -> Result<DifficultyMode> {
+    match mode.trim() {
+        "ramp" => Ok(DifficultyMode::Ramp),
+        "skill" => Ok(DifficultyMode::Skill),
+        mode => Err(Error::Config(format!("Unknown difficulty mode \"{}\".", mode))),
+    }
+}
+
+/// Parse the position of the scoreboard: `top` or `bottom`.
+///
+/// Returns an error if there is no such position.
+pub fn parse_scoreboard_position(position: &str) -> Result<ScoreboardPosition> {
+    match position.trim() {
+        "bottom" => Ok(ScoreboardPosition::Bottom),
+        "top" => Ok(ScoreboardPosition::Top),
+        position => Err(Error::Config(format!("Unknown scoreboard position \"{}\".", position))),
+    }
+}
+
+/// Parse the limit of a rally's length: a number of hits (e.g. `20`) or a duration in seconds (e.g. `30s`).
+///
+/// Returns an error if the limit is not a valid number.
+pub fn parse_rally_cap(cap: &str) -> Result<RallyCap> {
+    let cap: &str = cap.trim();
+    let parsed: Option<RallyCap> = match cap.strip_suffix('s') {
+        Some(seconds) => seconds.parse().ok().map(RallyCap::Seconds),
+        None => cap.parse().ok().map(RallyCap::Hits),
+    };
+    parsed.ok_or_else(|| Error::Config(format!("Invalid rally cap \"{}\".", cap)))
+}
+
+/// Parse a seed for the game's random choices, given as a non-negative integer.
+///
+/// Returns an error if the seed is not a valid number.
+pub fn parse_seed(seed: &str) -> Result<usize> {
+    let seed: &str = seed.trim();
+    seed.parse().map_err(|_| Error::Config(format!("Invalid seed \"{}\".", seed)))
+}
+
+/// Parse the score a player must reach to win the match, given as an integer.
+///
+/// Returns an error if the score is not a valid number.
+pub fn parse_target_score(score: &str) -> Result<isize> {
+    let score: &str = score.trim();
+    score.parse().map_err(|_| Error::Config(format!("Invalid target score \"{}\".", score)))
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            ai_dead_zone: None,
+            ai_decision_interval: 0.0,
+            aim_serves: false,
+            aspect_ratio: None,
+            ball_shrink: None,
+            ball_tail: false,
+            ball_visibility_range: None,
+            border_color: color::WHITE,
+            bounce_model: BounceModel::Reflect,
+            camera_follow: None,
+            center_line_color: color::GRAY,
+            controls_preset: ControlsPreset::Standard,
+            difficulty_mode: DifficultyMode::Ramp,
+            first_serve_delay: 0.0,
+            handicap: false,
+            handle_sizes: [DEFAULT_HANDLE_SIZE; 2],
+            input_sources: [InputSource::Keyboard; 2],
+            large_text: false,
+            lock_paddles_on_serve: false,
+            max_field_size: [7680, 4320],
+            max_score_digits: 6,
+            min_field_size: [100, 50],
+            min_horizontal_speed: 0.0,
+            min_rally_to_score: 0,
+            opengl: OpenGL::V3_2,
+            paddle_after_image: false,
+            paddle_collision: false,
+            pixel_snapping: false,
+            point_flash_colors: None,
+            practice_saves: 0,
+            rainbow_ball: false,
+            rally_cap: None,
+            recenter_paddles_on_point: false,
+            restitution: 1.0,
+            scoreboard_position: ScoreboardPosition::Top,
+            scoring_rule: ScoringRule::Standard,
+            seed: None,
+            serve_cone: None,
+            serve_delay: 0.0,
+            serve_speed: None,
+            show_serving_side: false,
+            speed_burst: None,
+            starting_scores: [0, 0],
+            target_score: None,
+            targets: Vec::new(),
+            toggle_debounce: 0.2,
+            top_line_collision: false,
+            vertical_wrap: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default() {
+        let config = Config::default();
+        assert!(config.ai_dead_zone.is_none());
+        assert_eq!(config.ai_decision_interval, 0.0);
+        assert!(!config.aim_serves);
+        assert_eq!(config.aspect_ratio, None);
+        assert!(config.ball_shrink.is_none());
+        assert!(!config.ball_tail);
+        assert!(config.ball_visibility_range.is_none());
+        assert_eq!(config.border_color, color::WHITE);
+        assert_eq!(config.bounce_model, BounceModel::Reflect);
+        assert!(config.camera_follow.is_none());
+        assert_eq!(config.center_line_color, color::GRAY);
+        assert_eq!(config.controls_preset, ControlsPreset::Standard);
+        assert_eq!(config.difficulty_mode, DifficultyMode::Ramp);
+        assert_eq!(config.first_serve_delay, 0.0);
+        assert!(!config.handicap);
+        assert_eq!(config.handle_sizes, [(10.0, 60.0); 2]);
+        assert_eq!(config.input_sources, [InputSource::Keyboard; 2]);
+        assert!(!config.large_text);
+        assert!(!config.lock_paddles_on_serve);
+        assert_eq!(config.max_field_size, [7680, 4320]);
+        assert_eq!(config.max_score_digits, 6);
+        assert_eq!(config.min_field_size, [100, 50]);
+        assert_eq!(config.min_horizontal_speed, 0.0);
+        assert_eq!(config.min_rally_to_score, 0);
+        assert_eq!(config.opengl, OpenGL::V3_2);
+        assert!(!config.paddle_after_image);
+        assert!(!config.paddle_collision);
+        assert!(!config.pixel_snapping);
+        assert_eq!(config.point_flash_colors, None);
+        assert_eq!(config.practice_saves, 0);
+        assert!(!config.rainbow_ball);
+        assert!(config.rally_cap.is_none());
+        assert!(!config.recenter_paddles_on_point);
+        assert_eq!(config.restitution, 1.0);
+        assert_eq!(config.scoreboard_position, ScoreboardPosition::Top);
+        assert_eq!(config.scoring_rule, ScoringRule::Standard);
+        assert!(config.seed.is_none());
+        assert!(config.serve_cone.is_none());
+        assert_eq!(config.serve_delay, 0.0);
+        assert_eq!(config.serve_speed, None);
+        assert!(!config.show_serving_side);
+        assert!(config.speed_burst.is_none());
+        assert_eq!(config.starting_scores, [0, 0]);
+        assert!(config.target_score.is_none());
+        assert!(config.targets.is_empty());
+        assert_eq!(config.toggle_debounce, 0.2);
+        assert!(!config.top_line_collision);
+        assert!(!config.vertical_wrap);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn parse_ai_level_valid() {
+        assert_eq!(parse_ai_level("easy").unwrap(), AiLevel::Easy);
+        assert_eq!(parse_ai_level(" hard\n").unwrap(), AiLevel::Hard);
+    }
+
+    #[test]
+    fn parse_ai_level_invalid() {
+        assert!(parse_ai_level("").is_err());
+        assert!(parse_ai_level("Hard").is_err());
+        assert!(parse_ai_level("impossible").is_err());
+    }
+
+    #[test]
+    fn parse_ball_shrink_valid() {
+        assert_eq!(parse_ball_shrink("collision").unwrap(), BallShrink::Collision);
+        assert_eq!(parse_ball_shrink(" drawn\n").unwrap(), BallShrink::Drawn);
+    }
+
+    #[test]
+    fn parse_ball_shrink_invalid() {
+        assert!(parse_ball_shrink("").is_err());
+        assert!(parse_ball_shrink("both").is_err());
+    }
+
+    #[test]
+    fn parse_bounce_model_valid() {
+        assert_eq!(parse_bounce_model("angled").unwrap(), BounceModel::Angled);
+        assert_eq!(parse_bounce_model("reflect").unwrap(), BounceModel::Reflect);
+        assert_eq!(parse_bounce_model(" segmented\n").unwrap(), BounceModel::Segmented(8));
+    }
+
+    #[test]
+    fn parse_bounce_model_invalid() {
+        assert!(parse_bounce_model("").is_err());
+        assert!(parse_bounce_model("Angled").is_err());
+    }
+
+    #[test]
+    fn parse_difficulty_mode_valid() {
+        assert_eq!(parse_difficulty_mode("ramp").unwrap(), DifficultyMode::Ramp);
+        assert_eq!(parse_difficulty_mode(" skill\n").unwrap(), DifficultyMode::Skill);
+    }
+
+    #[test]
+    fn parse_difficulty_mode_invalid() {
+        assert!(parse_difficulty_mode("").is_err());
+        assert!(parse_difficulty_mode("Skill").is_err());
+    }
+
+    #[test]
+    fn parse_opengl_valid() {
+        assert_eq!(parse_opengl("2.0").unwrap(), OpenGL::V2_0);
+        assert_eq!(parse_opengl("3.2").unwrap(), OpenGL::V3_2);
+        assert_eq!(parse_opengl(" 3.3\n").unwrap(), OpenGL::V3_3);
+        assert_eq!(parse_opengl("4.5").unwrap(), OpenGL::V4_5);
+    }
+
+    #[test]
+    fn parse_opengl_invalid() {
+        assert!(parse_opengl("").is_err());
+        assert!(parse_opengl("3").is_err());
+        assert!(parse_opengl("1.0").is_err());
+        assert!(parse_opengl("4.6").is_err());
+        assert!(parse_opengl("V3_2").is_err());
+    }
+
+    #[test]
+    fn parse_rally_cap_valid() {
+        assert_eq!(parse_rally_cap("20").unwrap(), RallyCap::Hits(20));
+        assert_eq!(parse_rally_cap("30s").unwrap(), RallyCap::Seconds(30.0));
+        assert_eq!(parse_rally_cap(" 2.5s\n").unwrap(), RallyCap::Seconds(2.5));
+    }
+
+    #[test]
+    fn parse_rally_cap_invalid() {
+        assert!(parse_rally_cap("").is_err());
+        assert!(parse_rally_cap("s").is_err());
+        assert!(parse_rally_cap("2.5").is_err());
+        assert!(parse_rally_cap("-3").is_err());
+        assert!(parse_rally_cap("30 seconds").is_err());
+    }
+
+    #[test]
+    fn parse_scoreboard_position_valid() {
+        assert_eq!(parse_scoreboard_position("bottom").unwrap(), ScoreboardPosition::Bottom);
+        assert_eq!(parse_scoreboard_position(" top\n").unwrap(), ScoreboardPosition::Top);
+    }
+
+    #[test]
+    fn parse_scoreboard_position_invalid() {
+        assert!(parse_scoreboard_position("").is_err());
+        assert!(parse_scoreboard_position("left").is_err());
+    }
+
+    #[test]
+    fn parse_seed_valid() {
+        assert_eq!(parse_seed("0").unwrap(), 0);
+        assert_eq!(parse_seed(" 1337\n").unwrap(), 1337);
+    }
+
+    #[test]
+    fn parse_seed_invalid() {
+        assert!(parse_seed("").is_err());
+        assert!(parse_seed("-1").is_err());
+        assert!(parse_seed("42.0").is_err());
+        assert!(parse_seed("seed").is_err());
+    }
+
+    #[test]
+    fn parse_target_score_valid() {
+        assert_eq!(parse_target_score("11").unwrap(), 11);
+        assert_eq!(parse_target_score(" 21\n").unwrap(), 21);
+    }
+
+    #[test]
+    fn parse_target_score_invalid() {
+        assert!(parse_target_score("").is_err());
+        assert!(parse_target_score("11.0").is_err());
+        assert!(parse_target_score("eleven").is_err());
+    }
+
+    #[test]
+    fn validate_ai_dead_zone() {
+        let config = Config { ai_dead_zone: Some(0.0), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { ai_dead_zone: Some(-0.1), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { ai_dead_zone: Some(::std::f64::INFINITY), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_ai_decision_interval() {
+        let config = Config { ai_decision_interval: 0.1, ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { ai_decision_interval: -0.1, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { ai_decision_interval: ::std::f64::NAN, ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_aspect_ratio() {
+        let config = Config { aspect_ratio: Some(4.0 / 3.0), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { aspect_ratio: Some(0.0), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { aspect_ratio: Some(::std::f64::INFINITY), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { aspect_ratio: Some(::std::f64::NAN), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_ball_visibility_range() {
+        let config = Config { ball_visibility_range: Some(50.0), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { ball_visibility_range: Some(-1.0), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { ball_visibility_range: Some(::std::f64::NAN), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_bounce_model() {
+        let config = Config { bounce_model: BounceModel::Segmented(8), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { bounce_model: BounceModel::Segmented(1), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { bounce_model: BounceModel::Segmented(0), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_camera_follow() {
+        let camera = CameraFollow { smoothing: 5.0, zoom: 2.0 };
+        let config = Config { camera_follow: Some(camera), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { camera_follow: Some(CameraFollow { zoom: 0.5, ..camera }), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { camera_follow: Some(CameraFollow { smoothing: 0.0, ..camera }), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { camera_follow: Some(CameraFollow { smoothing: ::std::f64::NAN, ..camera }),
+                              ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_field_size_limits() {
+        let config = Config { max_field_size: [400, 300], min_field_size: [400, 300], ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { max_field_size: [400, 300], min_field_size: [500, 100], ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { max_field_size: [400, 300], min_field_size: [100, 400], ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_handle_sizes() {
+        let config = Config { handle_sizes: [(10.0, 60.0), (20.0, 90.0)], ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { handle_sizes: [(10.0, 60.0), (0.0, 90.0)], ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { handle_sizes: [(10.0, -60.0), (10.0, 60.0)], ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { handle_sizes: [(::std::f64::INFINITY, 60.0), (10.0, 60.0)], ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { handle_sizes: [(10.0, 20.0), (10.0, 120.0)], ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { handle_sizes: [(10.0, 19.0), (10.0, 60.0)], ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { handle_sizes: [(10.0, 60.0), (10.0, 121.0)], ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { handle_sizes: [(10.0, ::std::f64::NAN), (10.0, 60.0)], ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_min_horizontal_speed() {
+        let config = Config { min_horizontal_speed: 0.25, ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { min_horizontal_speed: 1.0, ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { min_horizontal_speed: -0.25, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { min_horizontal_speed: 1.25, ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_speed_burst() {
+        let burst = SpeedBurst { duration: 2.0, interval: 10.0, multiplier: 1.5 };
+        let config = Config { speed_burst: Some(burst), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { speed_burst: Some(SpeedBurst { duration: 0.0, ..burst }), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { speed_burst: Some(SpeedBurst { interval: -1.0, ..burst }), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let burst = SpeedBurst { multiplier: ::std::f64::NAN, ..burst };
+        let config = Config { speed_burst: Some(burst), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_multiple_problems() {
+        let config = Config { restitution: 1.5, serve_delay: -1.0, ..Config::default() };
+        assert_eq!(config.validate(), Err(vec![
+            String::from("The restitution must be within (0, 1], but is 1.5."),
+            String::from("The serve delay must not be negative, but is -1."),
+        ]));
+
+        let config = Config { first_serve_delay: -1.0, min_horizontal_speed: 2.0, restitution: 0.0,
+                              serve_delay: -1.0, ..Config::default() };
+        assert_eq!(config.validate().unwrap_err().len(), 4);
+    }
+
+    #[test]
+    fn validate_rally_cap() {
+        let config = Config { rally_cap: Some(RallyCap::Hits(10)), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { rally_cap: Some(RallyCap::Seconds(30.0)), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { rally_cap: Some(RallyCap::Hits(0)), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { rally_cap: Some(RallyCap::Seconds(0.0)), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { rally_cap: Some(RallyCap::Seconds(::std::f64::NAN)), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_restitution() {
+        let config = Config { restitution: 0.5, ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { restitution: 0.0, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { restitution: 1.5, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { restitution: ::std::f64::NAN, ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_serve_cone() {
+        let config = Config { serve_cone: Some(0.0), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { serve_cone: Some(30.0), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { serve_cone: Some(-1.0), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { serve_cone: Some(90.0), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { serve_cone: Some(::std::f64::NAN), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_serve_delays() {
+        let config = Config { first_serve_delay: 3.0, serve_delay: 1.0, ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { first_serve_delay: -1.0, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { serve_delay: -1.0, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { serve_delay: ::std::f64::NAN, ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_serve_speed() {
+        let config = Config { serve_speed: Some(200.0), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { serve_speed: Some(0.0), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { serve_speed: Some(::std::f64::NAN), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_target_score() {
+        let config = Config { target_score: Some(11), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { target_score: Some(0), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { target_score: Some(-3), ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { starting_scores: [10, 3], target_score: Some(11), ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { starting_scores: [3, 11], target_score: Some(11), ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+
+    #[test]
+    fn validate_toggle_debounce() {
+        let config = Config { toggle_debounce: 0.0, ..Config::default() };
+        assert!(config.validate().is_ok());
+
+        let config = Config { toggle_debounce: -0.1, ..Config::default() };
+        assert!(config.validate().is_err());
+
+        let config = Config { toggle_debounce: ::std::f64::NAN, ..Config::default() };
+        assert!(config.validate().is_err());
+    }
+}
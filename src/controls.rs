@@ -0,0 +1,268 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Key bindings for the players.
+//!
+//! The bindings can be loaded from a file in a subset of the TOML format: a section per player (`[left]` and
+//! `[right]`), each containing the keys `up` and `down`. The values are the names of the keys as used by Piston, e.g.:
+//!
+//! ```toml
+//! [left]
+//! up = "W"
+//! down = "S"
+//! ```
+//!
+//! Bindings missing in the file keep the values of the selected preset.
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use piston_window::Key;
+
+use execution_flow::Error;
+use execution_flow::Result;
+
+/// Predefined key bindings for both players.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ControlsPreset {
+    /// The left player uses `W` and `S`, the right player uses the arrow keys.
+    Standard,
+
+    /// The left player uses `W` and `S`, the right player uses `I` and `K`, so both players can comfortably share one
+    /// (e.g. laptop) keyboard side by side.
+    SideBySide,
+}
+
+impl ControlsPreset {
+    /// Get the key bindings of this preset.
+    pub fn to_controls(self) -> Controls {
+        let right: PlayerControls = match self {
+            ControlsPreset::Standard => PlayerControls { down: Key::Down, up: Key::Up },
+            ControlsPreset::SideBySide => PlayerControls { down: Key::K, up: Key::I },
+        };
+
+        Controls {
+            players: [
+                PlayerControls { down: Key::S, up: Key::W },
+                right,
+            ],
+        }
+    }
+}
+
+/// The keys controlling a single player's handle.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct PlayerControls {
+    /// Move the handle down.
+    pub down: Key,
+
+    /// Move the handle up.
+    pub up: Key,
+}
+
+/// The keys controlling the players' handles.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Controls {
+    /// The controls of the left and the right player, respectively.
+    pub players: [PlayerControls; 2],
+}
+
+impl Controls {
+    /// Load the key bindings from the file at `path`. Bindings missing in the file are taken from `defaults`.
+    ///
+    /// Returns an error if the file cannot be read or contains invalid bindings.
+    pub fn load(path: &Path, defaults: Controls) -> Result<Controls> {
+        let mut content = String::new();
+        if let Err(error) = File::open(path).and_then(|mut file| file.read_to_string(&mut content)) {
+            return Err(Error::Config(format!("Could not read {}: {}", path.display(), error)));
+        }
+
+        Controls::parse(&content, defaults)
+    }
+
+    /// Parse the key bindings from the given `content`.
+    ///
+    /// Returns an error describing the first invalid line otherwise.
+    fn parse(content: &str, defaults: Controls) -> Result<Controls> {
+        let mut controls: Controls = defaults;
+        let mut player: Option<usize> = None;
+
+        for (index, line) in content.lines().enumerate() {
+            let line_number: usize = index + 1;
+            let line: &str = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            // A new section starts.
+            if line.starts_with('[') && line.ends_with(']') {
+                player = match line[1..line.len() - 1].trim() {
+                    "left" => Some(0),
+                    "right" => Some(1),
+                    section => {
+                        return Err(Error::Config(format!("Unknown section \"{}\" in line {}.", section, line_number)));
+                    }
+                };
+                continue;
+            }
+
+            // All other lines are bindings in the form `action = "Key"`.
+            let player: usize = match player {
+                Some(player) => player,
+                None => return Err(Error::Config(format!("Binding outside of a section in line {}.", line_number))),
+            };
+            let mut parts = line.splitn(2, '=');
+            let action: &str = parts.next().unwrap_or("").trim();
+            let name: &str = match parts.next() {
+                Some(name) => name.trim().trim_matches('"'),
+                None => return Err(Error::Config(format!("Missing \"=\" in line {}.", line_number))),
+            };
+            let key: Key = match parse_key(name) {
+                Some(key) => key,
+                None => return Err(Error::Config(format!("Unknown key \"{}\" in line {}.", name, line_number))),
+            };
+
+            match action {
+                "down" => controls.players[player].down = key,
+                "up" => controls.players[player].up = key,
+                _ => return Err(Error::Config(format!("Unknown action \"{}\" in line {}.", action, line_number))),
+            }
+        }
+
+        Ok(controls)
+    }
+}
+
+impl Default for Controls {
+    fn default() -> Controls {
+        ControlsPreset::Standard.to_controls()
+    }
+}
+
+/// Get the preset with the given `name`: `standard` or `side-by-side`.
+///
+/// Returns an error if there is no such preset.
+pub fn parse_preset(name: &str) -> Result<ControlsPreset> {
+    match name.trim() {
+        "standard" => Ok(ControlsPreset::Standard),
+        "side-by-side" => Ok(ControlsPreset::SideBySide),
+        name => Err(Error::Config(format!("Unknown controls preset \"{}\".", name))),
+    }
+}
+
+/// Get the key with the given `name`, as used by Piston (e.g. `"W"` or `"Up"`).
+pub fn parse_key(name: &str) -> Option<Key> {
+    // Piston cannot parse keys from their names. Instead, compare the name against all key codes Piston knows: the
+    // ASCII range and the range of special keys (cf. http://wiki.libsdl.org/SDLKeycodeLookup).
+    (0x00..0x80).chain(0x4000_0000..0x4000_0120)
+        .map(Key::from)
+        .filter(|key| *key != Key::Unknown)
+        .find(|key| format!("{:?}", key) == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use super::*;
+
+    /// Write `content` to a new file in the temporary directory and return its path.
+    fn create_file(name: &str, content: &str) -> PathBuf {
+        let path: PathBuf = env::temp_dir().join(format!("mief-{}-{}", name, ::std::process::id()));
+        let mut file = File::create(&path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+        path
+    }
+
+    #[test]
+    fn controls_preset_standard() {
+        let controls = ControlsPreset::Standard.to_controls();
+        assert_eq!(controls.players[0], PlayerControls { down: Key::S, up: Key::W });
+        assert_eq!(controls.players[1], PlayerControls { down: Key::Down, up: Key::Up });
+        assert_eq!(controls, Controls::default());
+    }
+
+    #[test]
+    fn controls_preset_side_by_side() {
+        let controls = ControlsPreset::SideBySide.to_controls();
+        assert_eq!(controls.players[0], PlayerControls { down: Key::S, up: Key::W });
+        assert_eq!(controls.players[1], PlayerControls { down: Key::K, up: Key::I });
+    }
+
+    #[test]
+    fn parse_key_names() {
+        assert_eq!(parse_key("W"), Some(Key::W));
+        assert_eq!(parse_key("Up"), Some(Key::Up));
+        assert_eq!(parse_key("F12"), Some(Key::F12));
+        assert_eq!(parse_key("NumPad8"), Some(Key::NumPad8));
+        assert_eq!(parse_key("Unknown"), None);
+        assert_eq!(parse_key("w"), None);
+    }
+
+    #[test]
+    fn parse_preset_names() {
+        assert_eq!(parse_preset("standard").unwrap(), ControlsPreset::Standard);
+        assert_eq!(parse_preset(" side-by-side\n").unwrap(), ControlsPreset::SideBySide);
+        assert!(parse_preset("").is_err());
+        assert!(parse_preset("SideBySide").is_err());
+    }
+
+    #[test]
+    fn parse() {
+        let content: &str = "# Swap the players' keys.\n\
+                             [left]\n\
+                             up = \"Up\"\n\
+                             down = \"Down\"  # Arrow keys.\n\
+                             \n\
+                             [right]\n\
+                             up = \"W\"\n\
+                             down = \"S\"\n";
+        let controls = Controls::parse(content, Controls::default()).unwrap();
+        assert_eq!(controls.players[0], PlayerControls { down: Key::Down, up: Key::Up });
+        assert_eq!(controls.players[1], PlayerControls { down: Key::S, up: Key::W });
+    }
+
+    #[test]
+    fn parse_partial() {
+        let controls = Controls::parse("[right]\nup = \"I\"\n", Controls::default()).unwrap();
+        assert_eq!(controls.players[0], Controls::default().players[0]);
+        assert_eq!(controls.players[1], PlayerControls { down: Key::Down, up: Key::I });
+    }
+
+    #[test]
+    fn parse_partial_preset() {
+        let controls = Controls::parse("[left]\nup = \"Q\"\n", ControlsPreset::SideBySide.to_controls()).unwrap();
+        assert_eq!(controls.players[0], PlayerControls { down: Key::S, up: Key::Q });
+        assert_eq!(controls.players[1], PlayerControls { down: Key::K, up: Key::I });
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Controls::parse("up = \"W\"", Controls::default()).is_err());
+        assert!(Controls::parse("[center]", Controls::default()).is_err());
+        assert!(Controls::parse("[left]\nup \"W\"", Controls::default()).is_err());
+        assert!(Controls::parse("[left]\nup = \"Foo\"", Controls::default()).is_err());
+        assert!(Controls::parse("[left]\njump = \"W\"", Controls::default()).is_err());
+    }
+
+    #[test]
+    fn load() {
+        let path: PathBuf = create_file("controls-valid.toml", "[left]\nup = \"I\"\ndown = \"K\"\n");
+        let controls = Controls::load(&path, Controls::default());
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(controls.unwrap().players[0], PlayerControls { down: Key::K, up: Key::I });
+    }
+
+    #[test]
+    fn load_missing_file() {
+        let path: PathBuf = env::temp_dir().join("mief-controls-missing.toml");
+        assert!(Controls::load(&path, Controls::default()).is_err());
+    }
+}
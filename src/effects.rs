@@ -0,0 +1,157 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Visual effects giving feedback on the game's events.
+
+use rand::Rng;
+
+use color;
+
+/// The duration of a point flash in seconds.
+const FLASH_DURATION: f64 = 0.3;
+
+/// The opacity of a point flash when it starts.
+const FLASH_OPACITY: f32 = 0.4;
+
+/// The duration of a screen shake in seconds.
+const SHAKE_DURATION: f64 = 0.2;
+
+/// The maximum offset of the screen during a screen shake in pixels.
+const SHAKE_MAGNITUDE: f64 = 8.0;
+
+/// Tint the field in a color for a short time, e.g. in the color of the side that scored a point.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PointFlash {
+    /// The color of the current flash.
+    color: [f32; 4],
+
+    /// The remaining duration of the current flash in seconds.
+    remaining: f64,
+}
+
+impl PointFlash {
+    /// Get the color of the flash at the current time. The opacity decays linearly to `0` over the flash's duration.
+    pub fn color(&self) -> [f32; 4] {
+        color::with_alpha(self.color, FLASH_OPACITY * (self.remaining / FLASH_DURATION) as f32)
+    }
+
+    /// Determine if the flash is currently visible.
+    pub fn is_visible(&self) -> bool {
+        self.remaining > 0.0
+    }
+
+    /// Start a new flash in the given `color`. A currently running flash is restarted.
+    pub fn start(&mut self, color: [f32; 4]) {
+        self.color = color;
+        self.remaining = FLASH_DURATION;
+    }
+
+    /// Advance the flash by the change in time `dt`.
+    pub fn update(&mut self, dt: f64) {
+        self.remaining = (self.remaining - dt).max(0.0);
+    }
+}
+
+/// Shake the screen for a short time, e.g. when a point has been scored.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ScreenShake {
+    /// The remaining duration of the current shake in seconds.
+    remaining: f64,
+}
+
+impl ScreenShake {
+    /// Get the maximum offset of the screen at the current time. The magnitude decays linearly to `0` over the
+    /// shake's duration.
+    pub fn magnitude(&self) -> f64 {
+        SHAKE_MAGNITUDE * self.remaining / SHAKE_DURATION
+    }
+
+    /// Get a random offset `(x, y)` of the screen, within the current magnitude in both directions.
+    pub fn offset<R: Rng>(&self, rng: &mut R) -> (f64, f64) {
+        let magnitude: f64 = self.magnitude();
+        if magnitude <= 0.0 {
+            return (0.0, 0.0);
+        }
+
+        (rng.gen_range(-magnitude, magnitude), rng.gen_range(-magnitude, magnitude))
+    }
+
+    /// Start a new shake. A currently running shake is restarted.
+    pub fn start(&mut self) {
+        self.remaining = SHAKE_DURATION;
+    }
+
+    /// Advance the shake by the change in time `dt`.
+    pub fn update(&mut self, dt: f64) {
+        self.remaining = (self.remaining - dt).max(0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand::StdRng;
+    use super::*;
+
+    #[test]
+    fn point_flash_default() {
+        let flash = PointFlash::default();
+        assert!(!flash.is_visible());
+        assert_eq!(flash.color()[3], 0.0);
+    }
+
+    #[test]
+    fn point_flash_update_decays() {
+        let mut flash = PointFlash::default();
+        flash.start(color::RED);
+        assert!(flash.is_visible());
+        assert_eq!(flash.color(), [1.0, 0.0, 0.0, FLASH_OPACITY]);
+
+        flash.update(FLASH_DURATION / 2.0);
+        assert_eq!(flash.color(), [1.0, 0.0, 0.0, FLASH_OPACITY / 2.0]);
+
+        flash.update(FLASH_DURATION);
+        assert!(!flash.is_visible());
+        assert_eq!(flash.color(), [1.0, 0.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn default() {
+        let shake = ScreenShake::default();
+        let mut rng = StdRng::from_seed(&[42]);
+        assert_eq!(shake.magnitude(), 0.0);
+        assert_eq!(shake.offset(&mut rng), (0.0, 0.0));
+    }
+
+    #[test]
+    fn start() {
+        let mut shake = ScreenShake::default();
+        shake.start();
+        assert_eq!(shake.magnitude(), SHAKE_MAGNITUDE);
+    }
+
+    #[test]
+    fn update_decays() {
+        let mut shake = ScreenShake::default();
+        let mut rng = StdRng::from_seed(&[42]);
+        shake.start();
+
+        let mut previous_magnitude: f64 = shake.magnitude();
+        for _ in 0..4 {
+            shake.update(0.05);
+            let magnitude: f64 = shake.magnitude();
+            let offset: (f64, f64) = shake.offset(&mut rng);
+            assert!(magnitude < previous_magnitude);
+            assert!(offset.0.abs() <= magnitude && offset.1.abs() <= magnitude);
+            previous_magnitude = magnitude;
+        }
+
+        // After the shake's duration, the screen is not offset anymore.
+        shake.update(0.05);
+        assert_eq!(shake.magnitude(), 0.0);
+        assert_eq!(shake.offset(&mut rng), (0.0, 0.0));
+    }
+}
@@ -6,15 +6,49 @@
 
 //! The ball used for playing and directly associated structures.
 
+use std::f64::consts::PI;
+
 use piston_window::Context;
 use piston_window::Ellipse;
 use piston_window::G2d;
 use piston_window::Transformed;
-use rand::thread_rng;
 use rand::Rng;
-use rand::ThreadRng;
+use rand::thread_rng;
 
 use color;
+use pixels;
+use config::BallShrink;
+use config::BounceModel;
+use elements::FieldSide;
+
+/// The diameter of the ball at its base speed.
+const DIAMETER: f64 = 10.0;
+
+/// The speed (in pixels per second, regardless of the direction) up to which a shrinking ball keeps its full size.
+const SHRINK_START_SPEED: f64 = 200.0;
+
+/// The speed (in pixels per second, regardless of the direction) at which a shrinking ball reaches its smallest size,
+/// i.e. the highest speed the ball can reach by speeding up.
+const SHRINK_END_SPEED: f64 = MAXIMUM_SPEED;
+
+/// The smallest diameter of a shrinking ball.
+const SHRINK_MINIMUM_DIAMETER: f64 = 4.0;
+
+/// The speed at which the hue of a rainbow ball changes, in degrees per second.
+const HUE_SPEED: f64 = 90.0;
+
+/// The highest total speed the ball can reach by speeding up, in pixels per second.
+const MAXIMUM_SPEED: f64 = 600.0;
+
+/// The largest number of steps into which a single update of the ball's position is divided to check for collisions.
+const MAXIMUM_SUBSTEPS: usize = 1000;
+
+/// The steepest angle between the ball's direction and the horizontal after bouncing off the outermost part of an
+/// angled or segmented handle, in radians.
+const MAXIMUM_BOUNCE_ANGLE: f64 = PI / 4.0;
+
+/// The time span covered by the ball's tail, i.e. the tail's length is the distance the ball travels in this time.
+const TAIL_DURATION: f64 = 0.1;
 
 /// The current status of the ball.
 #[cfg_attr(feature = "cargo-clippy", allow(stutter))]
@@ -26,30 +60,76 @@ pub enum BallStatus {
     /// The ball left the field on the right side.
     LeftOnRightSide,
 
+    /// The rally reached its limit without a point being scored. A new ball will be served.
+    Let,
+
     /// The ball is still within the field.
     WithinGame,
 }
 
 /// The ball used for playing.
-#[derive(Clone, Copy, Debug, Default)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Ball {
+    /// The way the ball bounces off the left and right sides of obstacles.
+    bounce_model: BounceModel,
+
     /// The diameter of the ball.
     diameter: f64,
 
+    /// The number of times the ball collided with any obstacle so far.
+    hits: usize,
+
+    /// The current hue of the ball if it cycles through the colors of the rainbow, in degrees within `[0, 360)`.
+    hue: f64,
+
+    /// The index of the obstacle the ball last collided with (if any).
+    last_collision: Option<usize>,
+
+    /// The minimum fraction of the ball's total speed that must be in the x-direction, preventing stalling rallies.
+    min_horizontal_speed: f64,
+
     /// The current position of the ball: `(x, y)`.
     position: (f64, f64),
 
+    /// Cycle the ball's color through the hue wheel instead of drawing it in white.
+    rainbow: bool,
+
+    /// If set, the ball shrinks as it speeds up.
+    shrink: Option<BallShrink>,
+
+    /// The fraction of its speed the ball keeps when bouncing off a wall or an obstacle.
+    restitution: f64,
+
     /// The current speed of the ball: `(x, y)`.
     speed: (f64, f64),
+
+    /// A transient factor by which the ball's speed is multiplied, e.g. during a speed burst.
+    speed_multiplier: f64,
+
+    /// Draw a tail behind the ball pointing in the opposite direction of its movement.
+    tail: bool,
+
+    /// Let the ball leave the field on the top and reappear at the bottom (and vice versa) instead of bouncing off.
+    vertical_wrap: bool,
+
+    /// If set, the ball is only drawn while its center is within this horizontal distance of a handle.
+    visibility_range: Option<f64>,
 }
 
 impl Ball {
-    /// Create a new ball with a random speed at the center of the window (given by `[width, height]`).
+    /// Create a new ball at the center of the window (given by `[width, height]`) with a random speed.
+    #[allow(dead_code)]  // Not used by the field, which chooses the speed with its own seeded generator.
     pub fn new(window_size: [u32; 2]) -> Ball {
+        Ball::new_with_rng(window_size, &mut thread_rng())
+    }
+
+    /// Create a new ball at the center of the window (given by `[width, height]`) with a speed chosen randomly by
+    /// `rng`.
+    pub fn new_with_rng<R: Rng>(window_size: [u32; 2], rng: &mut R) -> Ball {
         let width = f64::from(window_size[0]);
         let height = f64::from(window_size[1]);
 
-        let radius: f64 = 5.0;
+        let radius: f64 = DIAMETER / 2.0;
         let mut position: (f64, f64) = (width / 2.0 - radius, height / 2.0 - radius);
         if position.0 < 0.0 {
             position.0 = 0.0;
@@ -61,7 +141,6 @@ impl Ball {
         // Randomly choose the speed.
         let mininum_speed: f64 = 100.0;
         let maximum_speed: f64 = 150.0;
-        let mut rng: ThreadRng = thread_rng();
         let mut speed_x: f64 = rng.gen_range(mininum_speed, maximum_speed);
         if rng.gen::<bool>() {
             speed_x *= -1.0;
@@ -72,84 +151,468 @@ impl Ball {
         }
 
         Ball {
+            bounce_model: BounceModel::Reflect,
             diameter: radius * 2.0,
+            hits: 0,
+            hue: 0.0,
+            last_collision: None,
+            min_horizontal_speed: 0.0,
             position,
+            rainbow: false,
+            restitution: 1.0,
+            shrink: None,
             speed: (speed_x, speed_y),
+            speed_multiplier: 1.0,
+            tail: false,
+            vertical_wrap: false,
+            visibility_range: None,
         }
     }
 
-    /// Change the ball's speed by the given `amount` in both directions.
-    pub fn change_speed(&mut self, amount: f64) {
-        // Change the speed in the x-direction.
-        if self.speed.0.is_sign_positive() {
-            self.speed.0 += amount;
+    /// Create a new ball at the center of the window (given by `[width, height]`) moving towards the given `side` of
+    /// the window. The ball's speed and its angle to the horizontal (at most `max_angle`, in radians) are chosen
+    /// randomly by `rng`.
+    pub fn new_directed<R: Rng>(window_size: [u32; 2], rng: &mut R, side: FieldSide, max_angle: f64) -> Ball {
+        let mut ball = Ball::new_with_rng(window_size, rng);
+        let speed: f64 = ball.speed.0.hypot(ball.speed.1);
+        let angle: f64 = if max_angle > 0.0 {
+            rng.gen_range(-max_angle, max_angle)
         }
         else {
-            self.speed.0 -= amount;
+            0.0
+        };
+        let direction: f64 = match side {
+            FieldSide::Left => -1.0,
+            FieldSide::Right => 1.0,
+        };
+
+        ball.speed = (direction * speed * angle.cos(), speed * angle.sin());
+        ball
+    }
+
+    /// Create a new ball at the center of the window (given by `[width, height]`) with a random direction chosen by
+    /// `rng`, but a total speed of exactly `speed`, no matter how diagonal the direction is.
+    pub fn new_normalized<R: Rng>(window_size: [u32; 2], rng: &mut R, speed: f64) -> Ball {
+        let mut ball = Ball::new_with_rng(window_size, rng);
+        ball.set_total_speed(speed);
+        ball
+    }
+
+    /// Get the bounding box of the ball.
+    #[inline]
+    pub fn bounding_box(&self) -> [f64; 4] {
+        [
+            self.position.0,                  // Left x.
+            self.position.1,                  // Top y.
+            self.position.0 + self.diameter,  // Right x.
+            self.position.1 + self.diameter   // Bottom y.
+        ]
+    }
+
+    /// Change the ball's total speed by the given `amount`, scaling both directions proportionally so the ball keeps
+    /// its direction. The ball does not speed up beyond `MAXIMUM_SPEED` (but a faster ball is not slowed down either),
+    /// and does not slow down below a standstill.
+    pub fn change_speed(&mut self, amount: f64) {
+        let speed: f64 = self.speed.0.hypot(self.speed.1);
+        self.set_total_speed((speed + amount).max(0.0).min(MAXIMUM_SPEED.max(speed)));
+    }
+
+    /// Get the number of times the ball collided with any obstacle so far.
+    pub fn get_hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Get the index of the obstacle the ball last collided with, if it collided with any obstacle so far.
+    pub fn get_last_collision(&self) -> Option<usize> {
+        self.last_collision
+    }
+
+    /// Get the ball's current speed: `(x, y)`.
+    pub fn get_speed(&self) -> (f64, f64) {
+        self.speed
+    }
+
+    /// Get the transient factor by which the ball's speed is multiplied.
+    #[cfg(test)]
+    pub fn get_speed_multiplier(&self) -> f64 {
+        self.speed_multiplier
+    }
+
+    /// Set the way the ball bounces off the left and right sides of obstacles.
+    pub fn set_bounce_model(&mut self, bounce_model: BounceModel) {
+        self.bounce_model = bounce_model;
+    }
+
+    /// Set the minimum fraction of the ball's total speed that must be in the x-direction.
+    pub fn set_min_horizontal_speed(&mut self, fraction: f64) {
+        self.min_horizontal_speed = fraction;
+    }
+
+    /// Set if the ball's color cycles through the hue wheel instead of being white.
+    pub fn set_rainbow(&mut self, rainbow: bool) {
+        self.rainbow = rainbow;
+    }
+
+    /// Set if and how the ball shrinks as it speeds up.
+    pub fn set_shrink(&mut self, shrink: Option<BallShrink>) {
+        self.shrink = shrink;
+        self.update_diameter();
+    }
+
+    /// Set the ball's speed: `(x, y)`.
+    pub fn set_speed(&mut self, speed: (f64, f64)) {
+        self.speed = speed;
+    }
+
+    /// Set the transient factor by which the ball's speed is multiplied, without changing the ball's actual speed.
+    pub fn set_speed_multiplier(&mut self, multiplier: f64) {
+        self.speed_multiplier = multiplier;
+    }
+
+    /// Set the fraction of its speed the ball keeps when bouncing off a wall or an obstacle.
+    pub fn set_restitution(&mut self, restitution: f64) {
+        self.restitution = restitution;
+    }
+
+    /// Scale the ball's velocity to a total speed of `speed`, keeping its direction.
+    pub fn set_total_speed(&mut self, speed: f64) {
+        let current_speed: f64 = self.speed.0.hypot(self.speed.1);
+        if current_speed > 0.0 {
+            self.speed = (self.speed.0 * speed / current_speed, self.speed.1 * speed / current_speed);
+        }
+    }
+
+    /// Set if a tail is drawn behind the ball.
+    pub fn set_tail(&mut self, tail: bool) {
+        self.tail = tail;
+    }
+
+    /// Set if the ball wraps around vertically instead of bouncing off the top and bottom of the field.
+    pub fn set_vertical_wrap(&mut self, vertical_wrap: bool) {
+        self.vertical_wrap = vertical_wrap;
+    }
+
+    /// Set the horizontal distance to a handle within which the ball is drawn, or `None` to always draw the ball.
+    pub fn set_visibility_range(&mut self, range: Option<f64>) {
+        self.visibility_range = range;
+    }
+
+    /// Determine if the ball is drawn, given the x-positions of the handles' sides facing the field.
+    pub fn is_visible(&self, handle_xs: [f64; 2]) -> bool {
+        let range: f64 = match self.visibility_range {
+            Some(range) => range,
+            None => return true,
+        };
+
+        let center_x: f64 = self.position.0 + self.diameter / 2.0;
+        handle_xs.iter().any(|handle_x| (center_x - handle_x).abs() <= range)
+    }
+
+    /// Draw the ball, unless it is invisible given the x-positions of the handles' sides facing the field. If a
+    /// `pixel_scale` (device pixels per point) is given, the ball is snapped to whole pixels.
+    pub fn draw(&self, context: &Context, graphics: &mut G2d, handle_xs: [f64; 2], pixel_scale: Option<f64>) {
+        if !self.is_visible(handle_xs) {
+            return;
         }
 
-        // Change the speed in the y-direction.
-        if self.speed.1.is_sign_positive() {
-            self.speed.1 += amount;
+        let ball_color: [f32; 4] = if self.speed_multiplier > 1.0 {
+            color::RED
+        }
+        else if self.rainbow {
+            color::from_hsv(self.hue, 1.0, 1.0)
         }
         else {
-            self.speed.1 -= amount;
+            color::WHITE
+        };
+
+        // A ball only shrinking when drawn is drawn around the center of its full size.
+        let diameter: f64 = self.drawn_diameter();
+        let inset: f64 = (self.diameter - diameter) / 2.0;
+        let position: (f64, f64) = pixels::snap_point((self.position.0 + inset, self.position.1 + inset), pixel_scale);
+
+        // Draw the tail as a stretched ellipse from the ball's center in the opposite direction of its movement.
+        if self.tail {
+            let (angle, length): (f64, f64) = self.tail_geometry();
+            let radius: f64 = diameter / 2.0;
+            let tail_color: [f32; 4] = color::with_alpha(ball_color, ball_color[3] / 2.0);
+            let tail = Ellipse::new(tail_color).resolution(100);
+            let transformation = context.transform
+                .trans(position.0 + radius, position.1 + radius)
+                .rot_rad(angle);
+            tail.draw([-length, -radius / 2.0, length, radius], &context.draw_state, transformation, graphics);
+        }
+
+        let ball = Ellipse::new(ball_color).resolution(100);
+        let transformation = context.transform.trans(position.0, position.1);
+        ball.draw([0.0, 0.0, diameter, diameter], &context.draw_state, transformation, graphics);
+    }
+
+    /// Get the diameter at which the ball is drawn: shrunk according to its speed if it shrinks at all.
+    fn drawn_diameter(&self) -> f64 {
+        match self.shrink {
+            Some(_) => shrunk_diameter(self.speed.0.hypot(self.speed.1)),
+            None => self.diameter,
         }
     }
 
-    /// Draw the ball.
-    pub fn draw(&self, context: &Context, graphics: &mut G2d) {
-        let ball = Ellipse::new(color::WHITE).resolution(100);
-        let transformation = context.transform.trans(self.position.0, self.position.1);
-        ball.draw([0.0, 0.0, self.diameter, self.diameter], &context.draw_state, transformation, graphics);
+    /// Shrink (or grow) the ball's actual size according to its speed if it shrinks for collisions, or restore its full
+    /// size otherwise, keeping its center.
+    fn update_diameter(&mut self) {
+        let diameter: f64 = match self.shrink {
+            Some(BallShrink::Collision) => shrunk_diameter(self.speed.0.hypot(self.speed.1)),
+            _ => DIAMETER,
+        };
+        let offset: f64 = (self.diameter - diameter) / 2.0;
+        self.position = (self.position.0 + offset, self.position.1 + offset);
+        self.diameter = diameter;
+    }
+
+    /// Get the geometry of the ball's tail: the angle of the ball's movement (in radians, clockwise from the positive
+    /// x-axis) and the length of the tail, proportional to the ball's speed.
+    fn tail_geometry(&self) -> (f64, f64) {
+        let angle: f64 = self.speed.1.atan2(self.speed.0);
+        let length: f64 = self.speed.0.hypot(self.speed.1) * TAIL_DURATION;
+        (angle, length)
     }
 
     /// Update the ball's position. `dt` is the change in time since the last update, `width` and `height` are the
-    /// window's size.
-    pub fn update(&mut self, dt: f64, width: u32, height: u32, obstacles: &[[f64; 4]]) -> BallStatus {
-        let progress_x = self.speed.0 * dt;
-        let progress_y = self.speed.1 * dt;
-        let next_position: (f64, f64) = (self.position.0 + progress_x, self.position.1 + progress_y);
+    /// window's size. The ball bounces off the top of the field at `top_inset`, e.g. to match the border's thickness.
+    pub fn update(&mut self, dt: f64, width: u32, height: u32, top_inset: f64, obstacles: &[[f64; 4]]) -> BallStatus {
+        if self.rainbow {
+            self.hue = (self.hue + HUE_SPEED * dt) % 360.0;
+        }
+
+        self.prevent_stall();
+        if self.shrink == Some(BallShrink::Collision) {
+            self.update_diameter();
+        }
+
+        // A faster ball travels farther in the same time.
+        let dt: f64 = dt * self.speed_multiplier;
 
-        // Check for collisions with any obstacles.
-        for obstacle in obstacles {
-            self.collide_with(next_position, obstacle);
+        // Check for collisions with any obstacles along the ball's path, not only at its end, so a fast ball cannot
+        // pass through an obstacle within a single update. At the first collision, the ball moves up to its last
+        // position before the collision and continues from there in its new direction for the remaining time.
+        let path: (f64, f64) = (self.speed.0 * dt, self.speed.1 * dt);
+        let maximum_step: f64 = self.diameter / 2.0;
+        let steps: usize = if maximum_step > 0.0 {
+            ((path.0.hypot(path.1) / maximum_step).ceil() as usize).clamp(1, MAXIMUM_SUBSTEPS)
+        }
+        else {
+            1
+        };
+        let mut travelled: f64 = 0.0;
+        for step in 1..=steps {
+            let fraction: f64 = step as f64 / steps as f64;
+            let position: (f64, f64) = (self.position.0 + path.0 * fraction, self.position.1 + path.1 * fraction);
+            let mut collided: bool = false;
+            for (index, obstacle) in obstacles.iter().enumerate() {
+                if self.collide_with(position, obstacle) {
+                    self.hits += 1;
+                    self.last_collision = Some(index);
+                    collided = true;
+                }
+            }
+            if collided {
+                travelled = (step - 1) as f64 / steps as f64;
+                break;
+            }
         }
+        self.position = (self.position.0 + path.0 * travelled, self.position.1 + path.1 * travelled);
+        let dt: f64 = dt * (1.0 - travelled);
+        let progress_x = self.speed.0 * dt;
+        let progress_y = self.speed.1 * dt;
 
         // Will the ball leave the window on the x-axis? If so, it is a point for the other side's player.
-        let leaving_on_left_side: bool = self.position.0 + progress_x < 0.0;
+        let bounding_box: [f64; 4] = self.bounding_box();
+        let leaving_on_left_side: bool = bounding_box[0] + progress_x < 0.0;
         if leaving_on_left_side {
             return BallStatus::LeftOnLeftSide;
         }
-        let leaving_on_right_side: bool = self.position.0 + self.diameter + progress_x > f64::from(width);
+        let leaving_on_right_side: bool = bounding_box[2] + progress_x > f64::from(width);
         if leaving_on_right_side {
             return BallStatus::LeftOnRightSide;
         }
 
-        // Will the ball leave the window on the y-axis? If so, revert speed on y-axis.
-        let leaving_on_top: bool = self.position.1 + progress_y < 0.0;
-        let leaving_on_bottom: bool = self.position.1 + self.diameter + progress_y > f64::from(height);
-        if leaving_on_top || leaving_on_bottom {
-            self.speed.1 *= -1.0;
+        // Will the ball leave the window on the y-axis? If so, revert speed on y-axis (unless the ball wraps around).
+        let leaving_on_top: bool = bounding_box[1] + progress_y < top_inset;
+        let leaving_on_bottom: bool = bounding_box[3] + progress_y > f64::from(height);
+        if (leaving_on_top || leaving_on_bottom) && !self.vertical_wrap {
+            self.speed.1 *= -self.restitution;
         }
 
         // Move the ball to the new position.
         self.position = (self.position.0 + self.speed.0 * dt,
                          self.position.1 + self.speed.1 * dt);
 
-        // Ensure the ball is entirely within the window on the y-axis.
-        if self.position.1 < 0.0 {
-            self.position.1 = 0.0;
-        } else if self.position.1 + self.diameter > f64::from(height) {
+        // Let the ball reappear on the other side of the field if it wraps around.
+        if self.vertical_wrap {
+            let range: f64 = f64::from(height) - top_inset - self.diameter;
+            self.position.1 = if range > 0.0 {
+                top_inset + (self.position.1 - top_inset).rem_euclid(range)
+            }
+            else {
+                top_inset
+            };
+        }
+
+        // Ensure the ball is entirely within the window on the y-axis. If the window is smaller than the ball, keep the
+        // ball at the top so it is never drawn above the field (i.e. over the scoreboard).
+        if self.position.1 + self.diameter > f64::from(height) {
             self.position.1 = f64::from(height) - self.diameter;
         }
+        if self.position.1 < top_inset {
+            self.position.1 = top_inset;
+        }
 
         BallStatus::WithinGame
     }
 
+    /// Update the ball's position like `update`, but stop at the first collision with any of the `obstacles` within
+    /// this step instead of checking for collisions only at the step's end. The ball is moved exactly up to the
+    /// obstacle and reflected there.
+    ///
+    /// Returns the ball's status and the fraction (from `0` to `1`) of `dt` still remaining after the collision. To
+    /// complete the motion, call this method again with the remaining time. If there was no collision, the remaining
+    /// fraction is `0`.
+    #[allow(dead_code)]  // Not used until sub-stepping is implemented.
+    pub fn update_substep(&mut self, dt: f64, width: u32, height: u32, top_inset: f64, obstacles: &[[f64; 4]])
+                          -> (BallStatus, f64) {
+        self.prevent_stall();
+
+        let motion: (f64, f64) = (self.speed.0 * dt * self.speed_multiplier, self.speed.1 * dt * self.speed_multiplier);
+        let ball: [f64; 4] = self.bounding_box();
+
+        // Find the earliest collision within this step, as the fraction of the step and the collided obstacle.
+        let mut collision: Option<(f64, bool, usize)> = None;
+        for (index, obstacle) in obstacles.iter().enumerate() {
+            let entry_x: (f64, f64) = Ball::sweep_axis(ball[0], ball[2], motion.0, obstacle[0], obstacle[2]);
+            let entry_y: (f64, f64) = Ball::sweep_axis(ball[1], ball[3], motion.1, obstacle[1], obstacle[3]);
+            let entry: f64 = entry_x.0.max(entry_y.0);
+            let exit: f64 = entry_x.1.min(entry_y.1);
+            if entry >= exit || !(0.0..=1.0).contains(&entry) {
+                continue;
+            }
+
+            // The axis entered last is the one the ball hit the obstacle on.
+            let is_lateral: bool = entry_x.0 >= entry_y.0;
+            let is_earlier: bool = match collision {
+                Some((earliest, _, _)) => entry < earliest,
+                None => true,
+            };
+            if is_earlier {
+                collision = Some((entry, is_lateral, index));
+            }
+        }
+
+        let (entry, is_lateral, index): (f64, bool, usize) = match collision {
+            Some(collision) => collision,
+            None => return (self.update(dt, width, height, top_inset, &[]), 0.0),
+        };
+
+        let status: BallStatus = self.update(dt * entry, width, height, top_inset, &[]);
+        if status != BallStatus::WithinGame {
+            return (status, 0.0);
+        }
+
+        let obstacle: [f64; 4] = obstacles[index];
+        if is_lateral {
+            self.speed.0 *= -self.restitution;
+            let center_y: f64 = self.position.1 + self.diameter / 2.0;
+            self.deflect_from_handle((center_y - obstacle[1]) / (obstacle[3] - obstacle[1]));
+        }
+        else {
+            self.speed.1 *= -self.restitution;
+        }
+        self.hits += 1;
+        self.last_collision = Some(index);
+
+        (BallStatus::WithinGame, 1.0 - entry)
+    }
+
+    /// Get the fractions of a step at which an interval from `start` to `end` moving by `motion` starts and stops
+    /// overlapping the interval from `object_start` to `object_end` on one axis.
+    fn sweep_axis(start: f64, end: f64, motion: f64, object_start: f64, object_end: f64) -> (f64, f64) {
+        if motion > 0.0 {
+            ((object_start - end) / motion, (object_end - start) / motion)
+        }
+        else if motion < 0.0 {
+            ((object_end - start) / motion, (object_start - end) / motion)
+        }
+        else if end > object_start && start < object_end {
+            (f64::NEG_INFINITY, f64::INFINITY)
+        }
+        else {
+            (f64::INFINITY, f64::NEG_INFINITY)
+        }
+    }
+
+    /// Ensure the ball's speed in the x-direction is at least the minimum fraction of its total speed, so the ball does
+    /// not bounce (almost) vertically forever. The speed is redistributed from the y-direction, keeping the total speed
+    /// and the directions.
+    fn prevent_stall(&mut self) {
+        let total_speed: f64 = self.speed.0.hypot(self.speed.1);
+        let minimum_speed_x: f64 = self.min_horizontal_speed * total_speed;
+        if self.speed.0.abs() >= minimum_speed_x {
+            return;
+        }
+
+        self.speed.0 = minimum_speed_x.copysign(self.speed.0);
+        self.speed.1 = (total_speed * total_speed - minimum_speed_x * minimum_speed_x).sqrt().copysign(self.speed.1);
+    }
+
+    /// Send the ball back into the field from the given `side`, e.g. when a wall on that side catches it. The ball
+    /// keeps its speed.
+    pub fn bounce_back(&mut self, side: FieldSide) {
+        self.speed.0 = match side {
+            FieldSide::Left => self.speed.0.abs(),
+            FieldSide::Right => -self.speed.0.abs(),
+        };
+    }
+
+    /// Check if an obstacle (with the given `index`) moving vertically from its `previous` to its `current` bounding
+    /// box swept into the ball, e.g. a fast handle catching up with a slow ball. If so, the ball is pushed out of the
+    /// obstacle in the direction of the obstacle's movement, and moves away from it. The ball is not pushed out of the
+    /// field of the given `height`, nor above its `top_inset`.
+    ///
+    /// Returns `true` if the obstacle swept into the ball.
+    pub fn collide_with_moving(&mut self, index: usize, previous: [f64; 4], current: [f64; 4], height: u32,
+                               top_inset: f64) -> bool {
+        let delta: f64 = current[1] - previous[1];
+        if delta == 0.0 {
+            return false;
+        }
+
+        let ball: [f64; 4] = self.bounding_box();
+        let overlaps = |object: [f64; 4], top_y: f64, bottom_y: f64| -> bool {
+            ball[2] >= object[0] && ball[0] <= object[2] && ball[3] >= top_y && ball[1] <= bottom_y
+        };
+
+        // The ball must be within the area the obstacle swept over, but must not have been within the obstacle before.
+        let is_in_swept_area: bool = overlaps(current, previous[1].min(current[1]), previous[3].max(current[3]));
+        if !is_in_swept_area || overlaps(previous, previous[1], previous[3]) {
+            return false;
+        }
+
+        if delta > 0.0 {
+            self.position.1 = current[3];
+            self.speed.1 = self.speed.1.abs();
+        }
+        else {
+            self.position.1 = current[1] - self.diameter;
+            self.speed.1 = -self.speed.1.abs();
+        }
+        self.position.1 = self.position.1.min(f64::from(height) - self.diameter).max(top_inset);
+        self.hits += 1;
+        self.last_collision = Some(index);
+        true
+    }
+
     /// Check if the ball will collide with `object`'s bounding box at `next_position` and reverse the ball's
-    /// direction accordingly.
-    fn collide_with(&mut self, next_position: (f64, f64), object: &[f64; 4]) {
+    /// direction accordingly, reducing the speed in that direction by the ball's restitution.
+    ///
+    /// Returns `true` if the ball collided with the object.
+    fn collide_with(&mut self, next_position: (f64, f64), object: &[f64; 4]) -> bool {
         let radius: f64 = self.diameter / 2.0;
         let (x, y): (f64, f64) = next_position;
 
@@ -163,7 +626,7 @@ impl Ball {
             y + self.diameter >= top_y &&   // The ball must not be above the object.
             y <= bottom_y;                  // The ball must not be below the object.
         if hit_horizontal_edge {
-            self.speed.1 *= -1.0;
+            self.speed.1 *= -self.restitution;
         }
 
         // Did the ball hit the object on the left or right side?
@@ -173,16 +636,66 @@ impl Ball {
             x + self.diameter >= left_x &&  // The ball must not be to the left of the object.
             x <= right_x;                   // The ball must not be to the right of the object.
         if hit_lateral_edge {
-            self.speed.0 *= -1.0;
+            self.speed.0 *= -self.restitution;
+            self.deflect_from_handle((y + radius - top_y) / (bottom_y - top_y));
+        }
+
+        hit_horizontal_edge || hit_lateral_edge
+    }
+
+    /// Change the ball's direction after bouncing off the side of a handle according to the ball's bounce model.
+    /// `position` is where the ball hit the handle, from `0` (top) to `1` (bottom).
+    fn deflect_from_handle(&mut self, position: f64) {
+        match self.bounce_model {
+            BounceModel::Angled => self.deflect_from_offset(2.0 * position.clamp(0.0, 1.0) - 1.0),
+            BounceModel::Reflect => {},
+            BounceModel::Segmented(segments) => self.deflect_from_segment(segments, position),
+        }
+    }
+
+    /// Send the ball off at an angle given by the `offset` of the hit from the obstacle's center, from `-1` (top) to
+    /// `1` (bottom): the angle grows linearly with the offset up to `MAXIMUM_BOUNCE_ANGLE` at the edges. The ball keeps
+    /// its total speed and its horizontal direction.
+    ///
+    /// Setting the vertical speed to the offset times the total speed instead would send the ball off vertically after
+    /// hits on the very edges, without any horizontal speed left to reach the other side. Thus, the angle is capped.
+    fn deflect_from_offset(&mut self, offset: f64) {
+        let angle: f64 = MAXIMUM_BOUNCE_ANGLE * offset.clamp(-1.0, 1.0);
+        let speed: f64 = self.speed.0.hypot(self.speed.1);
+        self.speed = (speed * angle.cos() * self.speed.0.signum(), speed * angle.sin());
+    }
+
+    /// Send the ball off at the fixed angle of the segment it hit on an obstacle divided into `segments` segments,
+    /// keeping its total speed and its horizontal direction. `position` is where the ball hit the obstacle, from `0`
+    /// (top) to `1` (bottom).
+    fn deflect_from_segment(&mut self, segments: usize, position: f64) {
+        let angle: f64 = if segments > 1 {
+            let segment: usize = ((position.clamp(0.0, 1.0) * segments as f64) as usize).min(segments - 1);
+            MAXIMUM_BOUNCE_ANGLE * (2.0 * segment as f64 / (segments - 1) as f64 - 1.0)
         }
+        else {
+            0.0
+        };
+
+        let speed: f64 = self.speed.0.hypot(self.speed.1);
+        self.speed = (speed * angle.cos() * self.speed.0.signum(), speed * angle.sin());
     }
 }
 
+/// Get the diameter of a shrinking ball moving at the given total `speed`: the full diameter up to
+/// `SHRINK_START_SPEED`, shrinking linearly down to `SHRINK_MINIMUM_DIAMETER` at `SHRINK_END_SPEED` and above.
+fn shrunk_diameter(speed: f64) -> f64 {
+    let progress: f64 = ((speed - SHRINK_START_SPEED) / (SHRINK_END_SPEED - SHRINK_START_SPEED)).clamp(0.0, 1.0);
+    DIAMETER - (DIAMETER - SHRINK_MINIMUM_DIAMETER) * progress
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(trivial_casts)]
 
     use quickcheck::TestResult;
+    use rand::SeedableRng;
+    use rand::StdRng;
     use super::*;
 
     /// Two `f64` numbers are equal iff their difference is within `std::f64::EPSILON`.
@@ -221,24 +734,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn bounding_box() {
+        let mut ball = Ball::new([100, 100]);
+        ball.position = (20.0, 30.0);
+
+        let bounding_box = ball.bounding_box();
+        assert_eq!(bounding_box[0], ball.position.0);
+        assert_eq!(bounding_box[1], ball.position.1);
+        assert_eq!(bounding_box[2], ball.position.0 + ball.diameter);
+        assert_eq!(bounding_box[3], ball.position.1 + ball.diameter);
+    }
+
     #[test]
     fn change_speed_positive() {
-        let speed: (f64, f64) = (100.0, 100.0);
+        let speed: (f64, f64) = (30.0, 40.0);
         let mut ball = Ball::new([100, 100]);
         ball.speed = speed;
 
         ball.change_speed(10.0);
-        assert_eq!(ball.speed, (110.0, 110.0));
+        assert_eq!(ball.speed, (36.0, 48.0));
     }
 
     #[test]
     fn change_speed_negative() {
-        let speed: (f64, f64) = (-100.0, -100.0);
+        let speed: (f64, f64) = (-30.0, -40.0);
         let mut ball = Ball::new([100, 100]);
         ball.speed = speed;
 
         ball.change_speed(10.0);
-        assert_eq!(ball.speed, (-110.0, -110.0));
+        assert_eq!(ball.speed, (-36.0, -48.0));
+    }
+
+    #[test]
+    fn change_speed_slow_down() {
+        let mut ball = Ball::new([100, 100]);
+        ball.speed = (30.0, -40.0);
+
+        ball.change_speed(-25.0);
+        assert_eq!(ball.speed, (15.0, -20.0));
+
+        // Slowing down by more than the speed stops the ball instead of reversing it.
+        ball.change_speed(-100.0);
+        assert_eq!(ball.speed, (0.0, 0.0));
+    }
+
+    #[test]
+    fn change_speed_maximum() {
+        let mut ball = Ball::new([100, 100]);
+        ball.speed = (-120.0, 50.0);
+        let direction: f64 = ball.speed.1.atan2(ball.speed.0);
+
+        for _ in 0..100 {
+            ball.change_speed(10.0);
+            assert!(ball.speed.0.hypot(ball.speed.1) <= MAXIMUM_SPEED + 1e-9);
+            assert!((ball.speed.1.atan2(ball.speed.0) - direction).abs() < 1e-9);
+        }
+        assert!((ball.speed.0.hypot(ball.speed.1) - MAXIMUM_SPEED).abs() < 1e-9);
+
+        // A ball already faster than the maximum is not slowed down.
+        ball.speed = (800.0, 0.0);
+        ball.change_speed(10.0);
+        assert_eq!(ball.speed, (800.0, 0.0));
+    }
+
+    #[test]
+    fn new_no_collision() {
+        let ball = Ball::new([100, 100]);
+        assert_eq!(ball.get_last_collision(), None);
+        assert_eq!(ball.get_hits(), 0);
     }
 
     #[test]
@@ -249,7 +813,7 @@ mod tests {
         ball.speed = speed;
         assert_eq!(ball.position, (45.0, 45.0));
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::WithinGame);
         assert_eq!(ball.speed, speed);
         assert_eq!(ball.position, (55.0, 55.0));
@@ -262,7 +826,7 @@ mod tests {
         ball.speed = (100.0, -100.0);
         ball.position = (45.0, 5.0);
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::WithinGame);
         assert_eq!(ball.speed, (100.0, 100.0));
         assert_eq!(ball.position, (55.0, 15.0));
@@ -275,12 +839,88 @@ mod tests {
         ball.speed = (100.0, 100.0);
         ball.position = (45.0, 95.0);
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(ball.speed, (100.0, -100.0));
+        assert_eq!(ball.position, (55.0, 85.0));
+    }
+
+    #[test]
+    fn update_reflect_on_top_inset() {
+        let (width, height): (u32, u32) = (100, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (100.0, -100.0);
+        ball.position = (45.0, 12.0);
+
+        let status = ball.update(0.1, width, height, 5.0, &[]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(ball.speed, (100.0, 100.0));
+        assert_eq!(ball.position, (55.0, 22.0));
+    }
+
+    #[test]
+    fn update_reposition_to_top_inset() {
+        let (width, height): (u32, u32) = (100, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (100.0, 100.0);
+        ball.position = (45.0, 1.0);
+
+        let _ = ball.update(0.0, width, height, 5.0, &[]);
+        assert_eq!(ball.position, (45.0, 5.0));
+    }
+
+    #[test]
+    fn update_wrap_on_top() {
+        let (width, height): (u32, u32) = (100, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.set_vertical_wrap(true);
+        ball.speed = (100.0, -100.0);
+        ball.position = (45.0, 5.0);
+
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::WithinGame);
         assert_eq!(ball.speed, (100.0, -100.0));
         assert_eq!(ball.position, (55.0, 85.0));
     }
 
+    #[test]
+    fn update_wrap_on_bottom() {
+        let (width, height): (u32, u32) = (100, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.set_vertical_wrap(true);
+        ball.speed = (100.0, 100.0);
+        ball.position = (45.0, 85.0);
+
+        let status = ball.update(0.1, width, height, 0.0, &[]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(ball.speed, (100.0, 100.0));
+        assert_eq!(ball.position, (55.0, 5.0));
+    }
+
+    #[test]
+    fn update_wrap_small_field() {
+        let mut ball = Ball::new([100, 5]);
+        ball.set_vertical_wrap(true);
+        ball.speed = (0.0, 100.0);
+
+        let _ = ball.update(0.1, 100, 5, 0.0, &[]);
+        assert_eq!(ball.position.1, 0.0);
+    }
+
+    #[test]
+    fn update_reflect_with_restitution() {
+        let (width, height): (u32, u32) = (100, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.set_restitution(0.5);
+        ball.speed = (100.0, -100.0);
+        ball.position = (45.0, 5.0);
+
+        let status = ball.update(0.1, width, height, 0.0, &[]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(ball.speed, (100.0, 50.0));
+        assert_eq!(ball.position, (55.0, 10.0));
+    }
+
     #[test]
     fn update_reposition_to_top() {
         let (width, height): (u32, u32) = (100, 100);
@@ -288,7 +928,7 @@ mod tests {
         ball.speed = (100.0, -100.0);
         ball.position = (45.0, -15.0);
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::WithinGame);
         assert_eq!(ball.speed, (100.0, 100.0));
         assert_eq!(ball.position, (55.0, 0.0));
@@ -301,12 +941,176 @@ mod tests {
         ball.speed = (100.0, 100.0);
         ball.position = (45.0, 110.0);
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::WithinGame);
         assert_eq!(ball.speed, (100.0, -100.0));
         assert_eq!(ball.position, (55.0, 90.0));
     }
 
+    quickcheck! {
+        fn update_never_above_field(position_y: f64, speed: (f64, f64), dt: f64, height: u32) -> TestResult {
+            // Time only advances, the ball starts within the field.
+            if dt.is_sign_negative() || position_y.is_sign_negative() {
+                return TestResult::discard();
+            }
+
+            let width: u32 = 100;
+            let mut ball = Ball::new([width, height]);
+            ball.position = (45.0, position_y);
+            ball.speed = speed;
+
+            // Only balls remaining in the game are drawn at their new position.
+            if ball.update(dt, width, height, 0.0, &[]) != BallStatus::WithinGame {
+                return TestResult::discard();
+            }
+
+            // The field is drawn directly below the scoreboard, so the ball must never be above the field's top edge.
+            TestResult::from_bool(ball.bounding_box()[1] >= 0.0)
+        }
+    }
+
+    #[test]
+    fn update_reposition_small_field() {
+        let (width, height): (u32, u32) = (100, 5);
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (100.0, 100.0);
+        ball.position = (45.0, 0.0);
+
+        let status = ball.update(0.1, width, height, 0.0, &[]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(ball.position, (55.0, 0.0));
+    }
+
+    #[test]
+    fn shrunk_diameter_bounds() {
+        assert_eq!(shrunk_diameter(0.0), DIAMETER);
+        assert_eq!(shrunk_diameter(SHRINK_START_SPEED), DIAMETER);
+        assert_eq!(shrunk_diameter((SHRINK_START_SPEED + SHRINK_END_SPEED) / 2.0),
+                   (DIAMETER + SHRINK_MINIMUM_DIAMETER) / 2.0);
+        assert_eq!(shrunk_diameter(SHRINK_END_SPEED), SHRINK_MINIMUM_DIAMETER);
+        assert_eq!(shrunk_diameter(SHRINK_END_SPEED * 2.0), SHRINK_MINIMUM_DIAMETER);
+    }
+
+    #[test]
+    fn update_shrink_drawn() {
+        let mut ball = Ball::new([1000, 100]);
+        ball.set_shrink(Some(BallShrink::Drawn));
+        ball.speed = (SHRINK_END_SPEED, 0.0);
+        let _ = ball.update(0.1, 1000, 100, 0.0, &[]);
+
+        // Only the drawn ball shrinks.
+        assert_eq!(ball.drawn_diameter(), SHRINK_MINIMUM_DIAMETER);
+        assert_eq!(ball.diameter, DIAMETER);
+    }
+
+    #[test]
+    fn update_shrink_collision() {
+        let mut ball = Ball::new([1000, 100]);
+        ball.speed = (SHRINK_END_SPEED, 0.0);
+        ball.position = (100.0, 45.0);
+        ball.set_shrink(Some(BallShrink::Collision));
+        let _ = ball.update(0.0, 1000, 100, 0.0, &[]);
+
+        // The actual size shrinks around the ball's center.
+        assert_eq!(ball.drawn_diameter(), SHRINK_MINIMUM_DIAMETER);
+        assert_eq!(ball.diameter, SHRINK_MINIMUM_DIAMETER);
+        assert_eq!(ball.bounding_box(), [103.0, 48.0, 107.0, 52.0]);
+
+        // Disabling the shrinking restores the full size.
+        ball.set_shrink(None);
+        assert_eq!(ball.drawn_diameter(), DIAMETER);
+        assert_eq!(ball.bounding_box(), [100.0, 45.0, 110.0, 55.0]);
+    }
+
+    #[test]
+    fn tail_geometry() {
+        let ball = Ball { speed: (30.0, 40.0), ..Ball::default() };
+        let (angle, length): (f64, f64) = ball.tail_geometry();
+        assert!((angle - (4.0_f64 / 3.0).atan()).abs() < 1e-9);
+        assert!((length - 50.0 * TAIL_DURATION).abs() < 1e-9);
+
+        let ball = Ball { speed: (-100.0, 0.0), ..Ball::default() };
+        let (angle, length): (f64, f64) = ball.tail_geometry();
+        assert!((angle - ::std::f64::consts::PI).abs() < 1e-9);
+        assert!((length - 100.0 * TAIL_DURATION).abs() < 1e-9);
+
+        let ball = Ball { speed: (0.0, -20.0), ..Ball::default() };
+        let (angle, length): (f64, f64) = ball.tail_geometry();
+        assert!((angle + ::std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        assert!((length - 20.0 * TAIL_DURATION).abs() < 1e-9);
+    }
+
+    #[test]
+    fn update_speed_multiplier() {
+        let (width, height): (u32, u32) = (100, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (100.0, 50.0);
+        ball.position = (45.0, 45.0);
+        ball.set_speed_multiplier(2.0);
+
+        let _ = ball.update(0.1, width, height, 0.0, &[]);
+        assert_eq!(ball.speed, (100.0, 50.0));
+        assert_eq!(ball.position, (65.0, 55.0));
+    }
+
+    #[test]
+    fn update_hue() {
+        let mut ball = Ball::default();
+        let _ = ball.update(1.0, 100, 100, 0.0, &[]);
+        assert_eq!(ball.hue, 0.0);
+
+        ball.set_rainbow(true);
+        let _ = ball.update(1.0, 100, 100, 0.0, &[]);
+        assert_eq!(ball.hue, HUE_SPEED);
+    }
+
+    #[test]
+    fn update_hue_wraps() {
+        let mut ball = Ball { hue: 350.0, ..Ball::default() };
+        ball.set_rainbow(true);
+        let _ = ball.update(20.0 / HUE_SPEED, 100, 100, 0.0, &[]);
+        assert!((ball.hue - 10.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prevent_stall() {
+        let mut ball = Ball { speed: (-0.0, 100.0), ..Ball::default() };
+        ball.set_min_horizontal_speed(0.6);
+
+        ball.prevent_stall();
+        assert!((ball.speed.0 + 60.0).abs() < 1e-9);
+        assert!((ball.speed.1 - 80.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn prevent_stall_fast_enough() {
+        let mut ball = Ball { speed: (70.0, -10.0), ..Ball::default() };
+        ball.set_min_horizontal_speed(0.6);
+
+        ball.prevent_stall();
+        assert_eq!(ball.speed, (70.0, -10.0));
+    }
+
+    quickcheck! {
+        fn update_prevent_stall(speed: (f64, f64), fraction: f64) -> TestResult {
+            let is_valid_input: bool = speed.0.is_finite() && speed.1.is_finite() && (0.0..=1.0).contains(&fraction);
+            if !is_valid_input {
+                return TestResult::discard();
+            }
+
+            let mut ball = Ball { speed, ..Ball::default() };
+            ball.set_min_horizontal_speed(fraction);
+            let _ = ball.update(0.0, 100, 100, 0.0, &[]);
+
+            let total_speed: f64 = speed.0.hypot(speed.1);
+            let tolerance: f64 = 1e-9 * total_speed.max(1.0);
+            TestResult::from_bool(
+                ball.speed.0.abs() >= fraction * total_speed - tolerance &&
+                (ball.speed.0.hypot(ball.speed.1) - total_speed).abs() <= tolerance
+            )
+        }
+    }
+
     #[test]
     fn update_leave_on_left() {
         let (width, height): (u32, u32) = (100, 100);
@@ -314,7 +1118,7 @@ mod tests {
         ball.speed = (-100.0, 100.0);
         ball.position = (5.0, 45.0);
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::LeftOnLeftSide);
         assert_eq!(ball.speed, (-100.0, 100.0));
         assert_eq!(ball.position, (5.0, 45.0));
@@ -327,12 +1131,226 @@ mod tests {
         ball.speed = (100.0, 100.0);
         ball.position = (95.0, 45.0);
 
-        let status = ball.update(0.1, width, height, &[]);
+        let status = ball.update(0.1, width, height, 0.0, &[]);
         assert_eq!(status, BallStatus::LeftOnRightSide);
         assert_eq!(ball.speed, (100.0, 100.0));
         assert_eq!(ball.position, (95.0, 45.0));
     }
 
+    #[test]
+    fn new_seeded() {
+        // The same seed always creates the same ball.
+        let ball = Ball::new_with_rng([100, 100], &mut StdRng::from_seed(&[42]));
+        let other_ball = Ball::new_with_rng([100, 100], &mut StdRng::from_seed(&[42]));
+        assert_eq!(ball, other_ball);
+        assert_eq!(ball.speed, (119.13628882783034, -120.66419771592666));
+    }
+
+    #[test]
+    fn new_directed() {
+        let max_angle: f64 = PI / 6.0;
+        for seed in 0..500 {
+            let mut rng = StdRng::from_seed(&[seed]);
+            for &(side, direction) in &[(FieldSide::Left, -1.0), (FieldSide::Right, 1.0)] {
+                let ball = Ball::new_directed([100, 100], &mut rng, side, max_angle);
+                assert_eq!(ball.speed.0.signum(), direction);
+
+                let angle: f64 = (ball.speed.1 / ball.speed.0.abs()).atan();
+                assert!(angle.abs() <= max_angle, "Angle {} is outside of the cone.", angle);
+
+                let speed: f64 = ball.speed.0.hypot(ball.speed.1);
+                assert!(100.0 * 2.0_f64.sqrt() <= speed && speed <= 150.0 * 2.0_f64.sqrt());
+            }
+        }
+    }
+
+    #[test]
+    fn new_directed_without_cone() {
+        let ball = Ball::new_directed([100, 100], &mut StdRng::from_seed(&[42]), FieldSide::Left, 0.0);
+        assert!(ball.speed.0 < 0.0);
+        assert_eq!(ball.speed.1, 0.0);
+    }
+
+    #[test]
+    fn new_normalized() {
+        let mut directions: Vec<(f64, f64)> = Vec::new();
+        for seed in 0..500 {
+            let ball = Ball::new_normalized([100, 100], &mut StdRng::from_seed(&[seed]), 200.0);
+            let speed: f64 = ball.speed.0.hypot(ball.speed.1);
+            assert!((speed - 200.0).abs() < 1e-9, "Speed {} is not normalized.", speed);
+            directions.push(ball.speed);
+        }
+
+        // The direction is still random.
+        directions.dedup();
+        assert!(directions.len() > 1);
+    }
+
+    #[test]
+    fn set_total_speed() {
+        let mut ball = Ball { speed: (-30.0, 40.0), ..Ball::default() };
+        ball.set_total_speed(100.0);
+        assert_eq!(ball.speed, (-60.0, 80.0));
+
+        let mut ball = Ball { speed: (0.0, 0.0), ..Ball::default() };
+        ball.set_total_speed(100.0);
+        assert_eq!(ball.speed, (0.0, 0.0));
+    }
+
+    #[test]
+    fn is_visible() {
+        let mut ball = Ball { diameter: 10.0, ..Ball::default() };
+        let handle_xs: [f64; 2] = [20.0, 180.0];
+        for &x in &[-5.0, 10.0, 75.0, 95.0, 165.0, 195.0] {
+            ball.position = (x, 50.0);
+            assert!(ball.is_visible(handle_xs));
+        }
+
+        ball.set_visibility_range(Some(30.0));
+        let expectations: [(f64, bool); 8] = [
+            (-5.0, true), (10.0, true), (45.0, true), (45.1, false), (95.0, false), (145.0, true), (195.0, true),
+            (205.1, false),
+        ];
+        for &(x, is_visible) in &expectations {
+            ball.position = (x, 50.0);
+            assert_eq!(ball.is_visible(handle_xs), is_visible, "Ball at x = {}", x);
+        }
+    }
+
+    #[test]
+    fn deflect_from_segment() {
+        let mut ball = Ball { speed: (-30.0, 40.0), ..Ball::default() };
+        let diagonal: f64 = 50.0 * (PI / 4.0).cos();
+
+        // The outermost segments send the ball off at the steepest angle, upwards at the top and downwards at the
+        // bottom.
+        ball.deflect_from_segment(8, 0.05);
+        assert!((ball.speed.0 + diagonal).abs() < 1e-9 && (ball.speed.1 + diagonal).abs() < 1e-9);
+        ball.deflect_from_segment(8, 1.0);
+        assert!((ball.speed.0 + diagonal).abs() < 1e-9 && (ball.speed.1 - diagonal).abs() < 1e-9);
+
+        // The inner segments send the ball off at flatter angles.
+        ball.deflect_from_segment(8, 0.45);
+        let angle: f64 = (ball.speed.1 / ball.speed.0.abs()).atan();
+        assert!((angle + PI / 28.0).abs() < 1e-9);
+        ball.deflect_from_segment(8, 0.55);
+        let angle: f64 = (ball.speed.1 / ball.speed.0.abs()).atan();
+        assert!((angle - PI / 28.0).abs() < 1e-9);
+        assert!(ball.speed.0 < 0.0);
+        assert!((ball.speed.0.hypot(ball.speed.1) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn deflect_from_single_segment() {
+        let mut ball = Ball { speed: (30.0, 40.0), ..Ball::default() };
+        ball.deflect_from_segment(1, 0.1);
+        assert_eq!(ball.speed, (50.0, 0.0));
+    }
+
+    #[test]
+    fn update_collide_segmented() {
+        let (width, height): (u32, u32) = (100, 200);
+        let handle: [f64; 4] = [45.0, 40.0, 55.0, 100.0];
+        let mut ball = Ball { bounce_model: BounceModel::Segmented(3), diameter: 10.0, restitution: 1.0,
+                              speed_multiplier: 1.0, ..Ball::default() };
+
+        // A hit on the top segment sends the ball upwards, a hit on the center segment horizontally.
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 37.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert!(ball.speed.0 > 0.0 && ball.speed.1 < 0.0);
+
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 65.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert_eq!(ball.speed, (100.0, 0.0));
+
+        // A hit on the bottom segment sends the ball downwards.
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 85.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert!(ball.speed.0 > 0.0 && ball.speed.1 > 0.0);
+    }
+
+    #[test]
+    fn deflect_from_offset() {
+        let mut ball = Ball { speed: (-30.0, 40.0), ..Ball::default() };
+        ball.deflect_from_offset(0.0);
+        assert_eq!(ball.speed, (-50.0, 0.0));
+
+        ball.deflect_from_offset(-0.5);
+        let angle: f64 = (ball.speed.1 / ball.speed.0.abs()).atan();
+        assert!((angle + PI / 8.0).abs() < 1e-9);
+
+        ball.deflect_from_offset(0.5);
+        let angle: f64 = (ball.speed.1 / ball.speed.0.abs()).atan();
+        assert!((angle - PI / 8.0).abs() < 1e-9);
+        assert!(ball.speed.0 < 0.0);
+        assert!((ball.speed.0.hypot(ball.speed.1) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn deflect_from_offset_edges() {
+        // Hits on the very edges send the ball off at the steepest angle, but never vertically.
+        let diagonal: f64 = 50.0 * (PI / 4.0).cos();
+        let mut ball = Ball { speed: (-30.0, 40.0), ..Ball::default() };
+        ball.deflect_from_offset(-1.0);
+        assert!((ball.speed.0 + diagonal).abs() < 1e-9 && (ball.speed.1 + diagonal).abs() < 1e-9);
+
+        ball.deflect_from_offset(1.0);
+        assert!((ball.speed.0 + diagonal).abs() < 1e-9 && (ball.speed.1 - diagonal).abs() < 1e-9);
+    }
+
+    #[test]
+    fn update_collide_angled() {
+        let (width, height): (u32, u32) = (100, 200);
+        let handle: [f64; 4] = [45.0, 40.0, 55.0, 100.0];
+        let mut ball = Ball { bounce_model: BounceModel::Angled, diameter: 10.0, restitution: 1.0,
+                              speed_multiplier: 1.0, ..Ball::default() };
+
+        // A hit on the center of the handle sends the ball off (nearly) horizontally.
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 65.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert_eq!(ball.speed, (100.0, 0.0));
+
+        // A hit near the top sends the ball upwards, more steeply the closer the hit is to the top.
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 50.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert!(ball.speed.0 > 0.0 && ball.speed.1 < 0.0);
+        let flat_angle: f64 = ball.speed.1.atan2(ball.speed.0);
+
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 40.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert!(ball.speed.1.atan2(ball.speed.0) < flat_angle);
+        assert!((ball.speed.0.hypot(ball.speed.1) - 100.0).abs() < 1e-9);
+
+        // A hit near the bottom sends the ball downwards.
+        ball.speed = (-100.0, 0.0);
+        ball.position = (60.0, 85.0);
+        let _ = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert!(ball.speed.0 > 0.0 && ball.speed.1 > 0.0);
+        assert!((ball.speed.0.hypot(ball.speed.1) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn update_collide_fast() {
+        let (width, height): (u32, u32) = (300, 100);
+        let handle: [f64; 4] = [170.0, 0.0, 180.0, 100.0];
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (2000.0, 0.0);
+        ball.position = (100.0, 45.0);
+
+        // Within this update, the ball would move from far before the handle to far behind it.
+        let status = ball.update(0.1, width, height, 0.0, &[handle]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(ball.speed, (-2000.0, 0.0));
+        assert_eq!(ball.get_last_collision(), Some(0));
+        assert_eq!(ball.get_hits(), 1);
+    }
+
     #[test]
     fn update_collide() {
         let (width, height): (u32, u32) = (100, 100);
@@ -341,10 +1359,133 @@ mod tests {
         ball.speed = (-100.0, 100.0);
         ball.position = (65.0, 40.0);
 
-        let status = ball.update(0.1, width, height, &[object]);
+        // The ball touches the object after two thirds of its way and moves away from it for the remaining time.
+        let status = ball.update(0.1, width, height, 0.0, &[[0.0, 0.0, 5.0, 5.0], object]);
         assert_eq!(status, BallStatus::WithinGame);
         assert_eq!(ball.speed, (100.0, 100.0));
-        assert_eq!(ball.position, (75.0, 50.0));
+        assert!((ball.position.0 - 185.0 / 3.0).abs() < 1e-9 && (ball.position.1 - 50.0).abs() < 1e-9);
+        assert_eq!(ball.get_last_collision(), Some(1));
+        assert_eq!(ball.get_hits(), 1);
+    }
+
+    #[test]
+    fn update_substep_collide() {
+        let (width, height): (u32, u32) = (200, 100);
+        let obstacle: [f64; 4] = [50.0, 0.0, 60.0, 100.0];
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (50.0, 0.0);
+        ball.position = (20.0, 45.0);
+
+        // The ball touches the obstacle after 40% of the step and is reflected there.
+        let (status, remaining): (BallStatus, f64) = ball.update_substep(1.0, width, height, 0.0, &[obstacle]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert!((remaining - 0.6).abs() < 1e-9);
+        assert!((ball.position.0 - 40.0).abs() < 1e-9);
+        assert_eq!(ball.speed, (-50.0, 0.0));
+        assert_eq!(ball.get_last_collision(), Some(0));
+        assert_eq!(ball.get_hits(), 1);
+
+        // Continuing with the remaining time completes the motion away from the obstacle.
+        let (status, remaining): (BallStatus, f64) = ball.update_substep(remaining, width, height, 0.0, &[obstacle]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(remaining, 0.0);
+        assert!((ball.position.0 - 10.0).abs() < 1e-9);
+        assert_eq!(ball.get_hits(), 1);
+    }
+
+    #[test]
+    fn update_substep_no_collision() {
+        let (width, height): (u32, u32) = (200, 100);
+        let mut ball = Ball::new([width, height]);
+        ball.speed = (50.0, 0.0);
+        ball.position = (20.0, 45.0);
+
+        let (status, remaining): (BallStatus, f64) =
+            ball.update_substep(0.2, width, height, 0.0, &[[50.0, 0.0, 60.0, 100.0]]);
+        assert_eq!(status, BallStatus::WithinGame);
+        assert_eq!(remaining, 0.0);
+        assert_eq!(ball.position, (30.0, 45.0));
+        assert_eq!(ball.get_hits(), 0);
+    }
+
+    #[test]
+    fn bounce_back() {
+        let mut ball = Ball { speed: (-50.0, 20.0), ..Ball::default() };
+        ball.bounce_back(FieldSide::Left);
+        assert_eq!(ball.speed, (50.0, 20.0));
+        ball.bounce_back(FieldSide::Left);
+        assert_eq!(ball.speed, (50.0, 20.0));
+        ball.bounce_back(FieldSide::Right);
+        assert_eq!(ball.speed, (-50.0, 20.0));
+    }
+
+    #[test]
+    fn collide_with_moving_down() {
+        let mut ball = Ball { diameter: 10.0, position: (12.0, 85.0), speed: (-50.0, 20.0), ..Ball::default() };
+        let previous: [f64; 4] = [10.0, 20.0, 20.0, 80.0];
+        let current: [f64; 4] = [10.0, 35.0, 20.0, 95.0];
+
+        assert!(ball.collide_with_moving(1, previous, current, 200, 0.0));
+        assert_eq!(ball.position, (12.0, 95.0));
+        assert_eq!(ball.speed, (-50.0, 20.0));
+        assert_eq!(ball.last_collision, Some(1));
+        assert_eq!(ball.hits, 1);
+    }
+
+    #[test]
+    fn collide_with_moving_up() {
+        let mut ball = Ball { diameter: 10.0, position: (12.0, 15.0), speed: (-50.0, 20.0), ..Ball::default() };
+        let previous: [f64; 4] = [10.0, 40.0, 20.0, 100.0];
+        let current: [f64; 4] = [10.0, 10.0, 20.0, 70.0];
+
+        assert!(ball.collide_with_moving(0, previous, current, 200, 0.0));
+        assert_eq!(ball.position, (12.0, 0.0));
+        assert_eq!(ball.speed, (-50.0, -20.0));
+        assert_eq!(ball.last_collision, Some(0));
+    }
+
+    #[test]
+    fn collide_with_moving_passed_through() {
+        // The handle moved past the ball entirely within a single step.
+        let mut ball = Ball { diameter: 10.0, position: (12.0, 50.0), speed: (-50.0, -20.0), ..Ball::default() };
+        let previous: [f64; 4] = [10.0, 0.0, 20.0, 40.0];
+        let current: [f64; 4] = [10.0, 70.0, 20.0, 110.0];
+
+        assert!(ball.collide_with_moving(0, previous, current, 200, 0.0));
+        assert_eq!(ball.position, (12.0, 110.0));
+        assert_eq!(ball.speed, (-50.0, 20.0));
+    }
+
+    #[test]
+    fn collide_with_moving_within_field() {
+        // A handle at the bottom edge of the field does not push the ball out of the field.
+        let mut ball = Ball { diameter: 10.0, position: (12.0, 85.0), speed: (-50.0, 20.0), ..Ball::default() };
+        assert!(ball.collide_with_moving(1, [10.0, 20.0, 20.0, 80.0], [10.0, 40.0, 20.0, 100.0], 100, 0.0));
+        assert_eq!(ball.position, (12.0, 90.0));
+        assert_eq!(ball.speed, (-50.0, 20.0));
+
+        // Nor does a handle at the top push the ball above the top inset.
+        let mut ball = Ball { diameter: 10.0, position: (12.0, 15.0), speed: (-50.0, 20.0), ..Ball::default() };
+        assert!(ball.collide_with_moving(0, [10.0, 40.0, 20.0, 100.0], [10.0, 5.0, 20.0, 65.0], 100, 2.0));
+        assert_eq!(ball.position, (12.0, 2.0));
+        assert_eq!(ball.speed, (-50.0, -20.0));
+    }
+
+    #[test]
+    fn collide_with_moving_no_collision() {
+        let mut ball = Ball { diameter: 10.0, position: (50.0, 85.0), speed: (-50.0, 20.0), ..Ball::default() };
+        let expected: Ball = ball;
+
+        // The handle does not move.
+        assert!(!ball.collide_with_moving(0, [10.0, 20.0, 20.0, 80.0], [10.0, 20.0, 20.0, 80.0], 200, 0.0));
+
+        // The ball is not in the handle's way.
+        assert!(!ball.collide_with_moving(0, [10.0, 20.0, 20.0, 80.0], [10.0, 35.0, 20.0, 95.0], 200, 0.0));
+
+        // The ball was already touching the handle before it moved.
+        ball.position = (12.0, 75.0);
+        assert!(!ball.collide_with_moving(0, [10.0, 20.0, 20.0, 80.0], [10.0, 35.0, 20.0, 95.0], 200, 0.0));
+        assert_eq!(ball.speed, expected.speed);
     }
 
     #[test]
@@ -353,7 +1494,7 @@ mod tests {
         let old_speed: (f64, f64) = ball.speed;
         let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
 
-        ball.collide_with((25.0, 25.0), &object);
+        assert!(!ball.collide_with((25.0, 25.0), &object));
         assert_eq!(ball.speed, old_speed);
     }
 
@@ -363,17 +1504,42 @@ mod tests {
         let old_speed: (f64, f64) = ball.speed;
         let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
 
-        ball.collide_with((80.0, 65.0), &object);
+        assert!(ball.collide_with((80.0, 65.0), &object));
         assert_eq!(ball.speed, (old_speed.0, old_speed.1 * -1.0));
     }
 
+    #[test]
+    fn collide_with_on_corner() {
+        // The ball's center is exactly on the object's top left corner, thus, the ball hits both the top and the left
+        // edge. Currently, both directions are reversed, i.e. the ball bounces straight back.
+        let mut ball = Ball::new([100, 100]);
+        ball.speed = (100.0, 50.0);
+        let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
+
+        assert!(ball.collide_with((70.0, 70.0), &object));
+        assert_eq!(ball.speed, (-100.0, -50.0));
+    }
+
+    #[test]
+    fn collide_with_on_corner_with_restitution() {
+        // The ball's center is within the object near its bottom right corner, so both edges are hit and the
+        // restitution is applied in both directions.
+        let mut ball = Ball::new([100, 100]);
+        ball.speed = (-100.0, -50.0);
+        ball.set_restitution(0.5);
+        let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
+
+        assert!(ball.collide_with((78.0, 78.0), &object));
+        assert_eq!(ball.speed, (50.0, 25.0));
+    }
+
     #[test]
     fn collide_with_on_right() {
         let mut ball = Ball::new([100, 100]);
         let old_speed: (f64, f64) = ball.speed;
         let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
 
-        ball.collide_with((85.0, 80.0), &object);
+        assert!(ball.collide_with((85.0, 80.0), &object));
         assert_eq!(ball.speed, (old_speed.0 * -1.0, old_speed.1));
     }
 
@@ -383,17 +1549,39 @@ mod tests {
         let old_speed: (f64, f64) = ball.speed;
         let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
 
-        ball.collide_with((80.0, 85.0), &object);
+        assert!(ball.collide_with((80.0, 85.0), &object));
         assert_eq!(ball.speed, (old_speed.0, old_speed.1 * -1.0));
     }
 
+    #[test]
+    fn collide_with_restitution_on_top() {
+        let mut ball = Ball::new([100, 100]);
+        ball.set_restitution(0.5);
+        ball.speed = (100.0, 100.0);
+        let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
+
+        assert!(ball.collide_with((80.0, 65.0), &object));
+        assert_eq!(ball.speed, (100.0, -50.0));
+    }
+
+    #[test]
+    fn collide_with_restitution_on_left() {
+        let mut ball = Ball::new([100, 100]);
+        ball.set_restitution(0.5);
+        ball.speed = (100.0, 100.0);
+        let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
+
+        assert!(ball.collide_with((65.0, 80.0), &object));
+        assert_eq!(ball.speed, (-50.0, 100.0));
+    }
+
     #[test]
     fn collide_with_on_left() {
         let mut ball = Ball::new([100, 100]);
         let old_speed: (f64, f64) = ball.speed;
         let object: [f64; 4] = [75.0, 75.0, 85.0, 85.0];
 
-        ball.collide_with((65.0, 80.0), &object);
+        assert!(ball.collide_with((65.0, 80.0), &object));
         assert_eq!(ball.speed, (old_speed.0 * -1.0, old_speed.1));
     }
 }
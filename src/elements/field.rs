@@ -6,38 +6,226 @@
 
 //! The playing field of the game.
 
+use std::f64::consts::PI;
+use std::path::Path;
+
 use piston_window::Button;
+use piston_window::ButtonState;
 use piston_window::Context;
+use piston_window::Ellipse;
 use piston_window::G2d;
+use piston_window::Input;
 use piston_window::Key;
 use piston_window::Line;
+use piston_window::Rectangle;
+use piston_window::Touch;
 use piston_window::Transformed;
 use piston_window::UpdateArgs;
+use rand::random;
+use rand::Rng;
+use rand::SeedableRng;
+use rand::StdRng;
 
-use color;
+use config::AiLevel;
+use config::Config;
+use config::DifficultyMode;
+use config::InputSource;
+use config::RallyCap;
+use config::ScoringRule;
+use config::SpeedBurst;
+use controls::Controls;
+use controls::PlayerControls;
+use effects::PointFlash;
 use elements::Ball;
 use elements::BallStatus;
 use elements::FieldSide;
 use elements::Movement;
 use elements::Player;
+use execution_flow::Result;
+use pixels;
+
+/// The amount by which the aim of a serve is changed per key press, in radians.
+const AIM_ANGLE_CHANGE: f64 = PI / 36.0;
+
+/// The maximum angle between the direction of an aimed serve and the horizontal, in radians.
+const MAXIMUM_AIM_ANGLE: f64 = PI / 3.0;
+
+/// The amount by which a player's handle grows or shrinks per point of difference between the scores in handicap mode.
+const HANDICAP_HEIGHT_CHANGE: f64 = 5.0;
+
+/// The width of the lines drawn on the field.
+const LINE_WIDTH: f64 = 1.0;
 
 /// The interval at which the ball's and the players' speeds are changed.
 const SPEED_CHANGE_INTERVAL: f64 = 10.0;
 
+/// The key pausing and resuming the game.
+const PAUSE_KEY: Key = Key::P;
+
 /// The amount by which the speeds of the ball and players are changed.
 const SPEED_CHANGE: f64 = 10.0;
 
+/// The key switching the side controlled with the keyboard when playing alone against the computer.
+const SWITCH_SIDE_KEY: Key = Key::Tab;
+
+/// Statistics about the match played on a field.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct MatchStats {
+    /// The number of points the left and the right player, respectively, scored on serves the opponent never touched.
+    pub aces: [usize; 2],
+
+    /// The largest number of times the ball hit a handle within a single rally.
+    pub longest_rally: usize,
+
+    /// The number of points won by the left and the right player, respectively.
+    pub points: [usize; 2],
+}
+
+/// The state of a match.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum GameState {
+    /// The match is still being played.
+    Playing,
+
+    /// The player on the given side won the match.
+    Won(FieldSide),
+}
+
+/// The mutable simulation state of a field, used to rewind the field to an earlier state.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldSnapshot {
+    /// The time remaining until the computer reconsiders how to move the handle of each player it controls:
+    /// `[left, right]`.
+    ai_decision_countdowns: [f64; 2],
+
+    /// The angle between the direction of the next serve and the horizontal, in radians.
+    aim_angle: f64,
+
+    /// The ball used for playing.
+    ball: Ball,
+
+    /// The total simulated time.
+    elapsed_time: f64,
+
+    /// The flash in the color of the side that scored the last point.
+    flash: PointFlash,
+
+    /// The Δt since the last speed change.
+    last_speed_change: f64,
+
+    /// The players.
+    players: [Player; 2],
+
+    /// The time since the ball was served.
+    rally_time: f64,
+
+    /// The random number generator choosing the speeds of new balls.
+    rng: StdRng,
+
+    /// The number of misses the practice wall behind each player's handle still returns: `[left, right]`.
+    saves_remaining: [usize; 2],
+
+    /// The time remaining until the ball is served.
+    serve_countdown: f64,
+
+    /// The time since the last speed burst of the ball ended.
+    speed_burst_timer: f64,
+
+    /// The statistics about the match.
+    stats: MatchStats,
+}
+
+impl PartialEq for FieldSnapshot {
+    /// Random number generators cannot be compared directly. Instead, two generators are equal if copies of them
+    /// generate the same numbers.
+    fn eq(&self, other: &FieldSnapshot) -> bool {
+        let (mut rng, mut other_rng): (StdRng, StdRng) = (self.rng, other.rng);
+        self.ai_decision_countdowns == other.ai_decision_countdowns &&
+            self.aim_angle == other.aim_angle &&
+            self.ball == other.ball &&
+            self.elapsed_time == other.elapsed_time &&
+            self.flash == other.flash &&
+            self.last_speed_change == other.last_speed_change &&
+            self.players == other.players &&
+            self.rally_time == other.rally_time &&
+            rng.gen::<[u64; 4]>() == other_rng.gen::<[u64; 4]>() &&
+            self.saves_remaining == other.saves_remaining &&
+            self.serve_countdown == other.serve_countdown &&
+            self.speed_burst_timer == other.speed_burst_timer &&
+            self.stats == other.stats
+    }
+}
+
 /// The field where the game actually occurs.
 pub struct Field {
+    /// The time remaining until the computer reconsiders how to move the handle of each player it controls:
+    /// `[left, right]`.
+    ai_decision_countdowns: [f64; 2],
+
+    /// The angle between the direction of the next serve and the horizontal, in radians. Positive angles point
+    /// downwards. Only used if serves are aimed.
+    aim_angle: f64,
+
     /// The ball used for playing.
     ball: Ball,
 
+    /// The settings customizing the game.
+    config: Config,
+
+    /// The side of the player controlled with the keyboard when playing alone against the computer.
+    controlled_side: FieldSide,
+
+    /// The key bindings for the players.
+    controls: Controls,
+
+    /// The total simulated time, independent of any rendering.
+    elapsed_time: f64,
+
+    /// The flash in the color of the side that scored the last point.
+    flash: PointFlash,
+
+    /// Stop all updates of the field, e.g. while the game is paused.
+    frozen: bool,
+
+    /// The toggle keys currently held down. They do not act again until they have been released.
+    held_toggles: Vec<Key>,
+
     /// The Δt since the last speed change.
     last_speed_change: f64,
 
+    /// The total simulated times at which the toggle keys last acted.
+    last_toggles: Vec<(Key, f64)>,
+
     /// The players.
     players: [Player; 2],
 
+    /// The time since the ball was served.
+    rally_time: f64,
+
+    /// The random number generator for all random choices on the field, e.g. the speeds of new balls.
+    rng: StdRng,
+
+    /// The number of misses the practice wall behind each player's handle still returns: `[left, right]`.
+    saves_remaining: [usize; 2],
+
+    /// A function called with the side and the new score of a player whenever the player's score changes.
+    score_hook: Option<Box<dyn FnMut(FieldSide, isize)>>,
+
+    /// The seed of the random number generator.
+    seed: usize,
+
+    /// The time remaining until the ball is served. The ball does not move before it is served.
+    serve_countdown: f64,
+
+    /// The time since the last speed burst of the ball ended.
+    speed_burst_timer: f64,
+
+    /// The state of the match, telling whether a player has won it.
+    state: GameState,
+
+    /// The statistics about the match.
+    stats: MatchStats,
+
     /// The height of the field.
     height: u32,
 
@@ -46,20 +234,178 @@ pub struct Field {
 }
 
 impl Field {
-    /// Initialize a new playing field with the given size.
-    pub fn new(size: [u32; 2]) -> Field {
-        Field {
-            ball: Ball::new(size),
+    /// Initialize a new playing field with the given size, customized by `config`.
+    pub fn new(size: [u32; 2], config: &Config) -> Field {
+        let mut players: [Player; 2] = [
+            Player::new(FieldSide::Left, size[0], config.handle_sizes[0]),
+            Player::new(FieldSide::Right, size[0], config.handle_sizes[1])
+        ];
+        for (player, &score) in players.iter_mut().zip(config.starting_scores.iter()) {
+            player.move_to_center(size[1]);
+            player.set_after_image(config.paddle_after_image);
+            player.set_score(score);
+        }
+
+        let seed: usize = config.seed.unwrap_or_else(random);
+        let mut rng: StdRng = StdRng::from_seed(&[seed]);
+
+        let mut field = Field {
+            ai_decision_countdowns: [0.0; 2],
+            aim_angle: 0.0,
+            ball: Field::create_ball(size, config, &mut rng, None),
+            config: config.clone(),
+            controlled_side: match Field::solo_index(&config.input_sources) {
+                Some(1) => FieldSide::Right,
+                _ => FieldSide::Left,
+            },
+            controls: config.controls_preset.to_controls(),
+            elapsed_time: 0.0,
+            flash: PointFlash::default(),
+            frozen: false,
+            held_toggles: Vec::new(),
             last_speed_change: 0.0,
-            players: [
-                Player::new(FieldSide::Left, size[0]),
-                Player::new(FieldSide::Right, size[0])
-            ],
+            last_toggles: Vec::new(),
+            players,
+            rally_time: 0.0,
+            rng,
+            saves_remaining: [Field::initial_saves(config, 0), Field::initial_saves(config, 1)],
+            score_hook: None,
+            seed,
+            serve_countdown: config.first_serve_delay,
+            speed_burst_timer: 0.0,
+            state: GameState::Playing,
+            stats: MatchStats::default(),
             height: size[1],
             width: size[0],
+        };
+        field.state = field.game_state();
+        field
+    }
+
+    /// Add `points` to the score of the player on the given `side` of the field.
+    pub fn add_player_score(&mut self, side: FieldSide, points: isize) {
+        self.players[Field::player_index(side)].update_score(points);
+    }
+
+    /// Get the distance from the top of the field at which the ball bounces off: the thickness of the top line if the
+    /// line collides with the ball, `0` otherwise.
+    fn top_inset(&self) -> f64 {
+        if self.config.top_line_collision {
+            // The line is drawn with a radius of its width around its position, which in turn is its width.
+            LINE_WIDTH * 2.0
+        }
+        else {
+            0.0
+        }
+    }
+
+    /// Adjust the heights of the players' handles depending on the difference between their scores: the leading
+    /// player's handle shrinks, the trailing player's handle grows, starting from their configured heights.
+    fn apply_handicap(&mut self) {
+        let scores: [isize; 2] = self.get_player_scores();
+        let lead: f64 = scores[0].saturating_sub(scores[1]) as f64;
+        let heights: (f64, f64) = (self.config.handle_sizes[0].1, self.config.handle_sizes[1].1);
+        self.players[0].set_height(heights.0 - lead * HANDICAP_HEIGHT_CHANGE, self.height);
+        self.players[1].set_height(heights.1 + lead * HANDICAP_HEIGHT_CHANGE, self.height);
+    }
+
+    /// Create a new ball at the center of a field with the given size, customized by `config`. The ball's speed is
+    /// chosen by `rng`. If serves are directed by the config, the ball moves `towards` the given side.
+    fn create_ball(size: [u32; 2], config: &Config, rng: &mut StdRng, towards: Option<FieldSide>) -> Ball {
+        let mut ball = match (config.serve_cone, towards, config.serve_speed) {
+            (Some(cone), Some(side), speed) => {
+                let mut ball = Ball::new_directed(size, rng, side, cone.to_radians());
+                if let Some(speed) = speed {
+                    ball.set_total_speed(speed);
+                }
+                ball
+            },
+            (_, _, Some(speed)) => Ball::new_normalized(size, rng, speed),
+            _ => Ball::new_with_rng(size, rng),
+        };
+        ball.set_bounce_model(config.bounce_model);
+        ball.set_min_horizontal_speed(config.min_horizontal_speed);
+        ball.set_rainbow(config.rainbow_ball);
+        ball.set_restitution(config.restitution);
+        ball.set_shrink(config.ball_shrink);
+        ball.set_tail(config.ball_tail);
+        ball.set_vertical_wrap(config.vertical_wrap);
+        ball.set_visibility_range(config.ball_visibility_range);
+        ball
+    }
+
+    /// Get the position of the ball's center on the field: `(x, y)`.
+    pub fn ball_center(&self) -> (f64, f64) {
+        let bounding_box: [f64; 4] = self.ball.bounding_box();
+        ((bounding_box[0] + bounding_box[2]) / 2.0, (bounding_box[1] + bounding_box[3]) / 2.0)
+    }
+
+    /// Get the current velocity of the ball, in pixels per second along each axis.
+    pub fn ball_speed(&self) -> (f64, f64) {
+        self.ball.get_speed()
+    }
+
+    /// Get the current speed of the ball in pixels per second, regardless of its direction.
+    pub fn ball_speed_magnitude(&self) -> f64 {
+        let speed: (f64, f64) = self.ball_speed();
+        speed.0.hypot(speed.1)
+    }
+
+    /// Stop all updates of the field until it is unfrozen.
+    pub fn freeze(&mut self) {
+        self.frozen = true;
+    }
+
+    /// Continue updating the field after it has been frozen.
+    pub fn unfreeze(&mut self) {
+        self.frozen = false;
+    }
+
+    /// Determine if the ball's physics currently advance: the game must be running and the ball must have been
+    /// served.
+    pub fn is_active(&self) -> bool {
+        self.is_running() && self.serve_countdown <= 0.0
+    }
+
+    /// Determine if the game is running: the field must not be frozen and the match must not have been won yet.
+    fn is_running(&self) -> bool {
+        !self.frozen && self.state == GameState::Playing
+    }
+
+    /// Get the player who won the match, if any: a player wins once reaching the configured target score with a lead
+    /// of at least two points.
+    pub fn winner(&self) -> Option<FieldSide> {
+        match self.state {
+            GameState::Playing => None,
+            GameState::Won(side) => Some(side),
         }
     }
 
+    /// Determine the state of the match from the current scores.
+    fn game_state(&self) -> GameState {
+        let target_score: isize = match self.config.target_score {
+            Some(score) => score,
+            None => return GameState::Playing,
+        };
+
+        let scores: [isize; 2] = self.get_player_scores();
+        let lead: isize = scores[0].saturating_sub(scores[1]);
+        if scores[0] >= target_score && lead >= 2 {
+            GameState::Won(FieldSide::Left)
+        }
+        else if scores[1] >= target_score && lead <= -2 {
+            GameState::Won(FieldSide::Right)
+        }
+        else {
+            GameState::Playing
+        }
+    }
+
+    /// Get the total time simulated on this field (in seconds).
+    pub fn elapsed_seconds(&self) -> f64 {
+        self.elapsed_time
+    }
+
     /// Get the scores of all players.
     pub fn get_player_scores(&self) -> [isize; 2] {
         [
@@ -68,15 +414,207 @@ impl Field {
         ]
     }
 
+    /// Get the statistics about the match played so far.
+    pub fn get_stats(&self) -> MatchStats {
+        self.stats
+    }
+
+    /// Get the seed of the random number generator, e.g. to reproduce the game.
+    pub fn get_seed(&self) -> usize {
+        self.seed
+    }
+
+    /// Get the size of the field: `[width, height]`.
+    pub fn get_size(&self) -> [u32; 2] {
+        [self.width, self.height]
+    }
+
+    /// Get the side of the player serving the ball while the ball waits to be served, i.e. the side the ball will
+    /// move away from. Once the ball is in play, there is no serving side.
+    pub fn get_serving_side(&self) -> Option<FieldSide> {
+        if self.serve_countdown <= 0.0 {
+            return None;
+        }
+
+        if self.serve_velocity().0 < 0.0 {
+            Some(FieldSide::Right)
+        }
+        else {
+            Some(FieldSide::Left)
+        }
+    }
+
+    /// Get the players currently controlled with the keyboard, together with their key bindings. When playing alone
+    /// against the computer, the human player's keys control the player on the controlled side.
+    fn keyboard_players<'a>(&'a mut self) -> impl Iterator<Item = (&'a mut Player, PlayerControls)> + 'a {
+        let sources: [InputSource; 2] = [self.input_source(0), self.input_source(1)];
+        let solo_index: Option<usize> = Field::solo_index(&self.config.input_sources);
+        let controls: Controls = self.controls;
+        self.players.iter_mut()
+            .enumerate()
+            .filter(move |&(index, _)| sources[index] == InputSource::Keyboard)
+            .map(move |(index, player)| (player, controls.players[solo_index.unwrap_or(index)]))
+    }
+
+    /// Get the index of the player controlled with the keyboard if the other player is controlled by the computer,
+    /// given the configured input `sources`.
+    fn solo_index(sources: &[InputSource; 2]) -> Option<usize> {
+        match *sources {
+            [InputSource::Keyboard, InputSource::Ai(_)] => Some(0),
+            [InputSource::Ai(_), InputSource::Keyboard] => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Get the source currently controlling the player at `index`. When playing alone against the computer, the
+    /// keyboard controls the player on the controlled side, and the computer controls the other one.
+    fn input_source(&self, index: usize) -> InputSource {
+        let sources: [InputSource; 2] = self.config.input_sources;
+        match Field::solo_index(&sources) {
+            Some(solo_index) if index == Field::player_index(self.controlled_side) => sources[solo_index],
+            Some(solo_index) => sources[1 - solo_index],
+            None => sources[index],
+        }
+    }
+
+    /// Determine if a press of the toggle `key` acts, debouncing the key: a key held down (e.g. repeated by the
+    /// operating system) only acts once, and presses within the `debounce` time after the key's last action are
+    /// ignored.
+    fn accept_toggle(&mut self, key: Key, debounce: f64) -> bool {
+        let is_debounced: bool = match self.last_toggles.iter().find(|&&(toggle, _)| toggle == key) {
+            Some(&(_, last_toggle)) => self.elapsed_time - last_toggle < debounce,
+            None => false,
+        };
+        if self.held_toggles.contains(&key) || is_debounced {
+            return false;
+        }
+
+        self.held_toggles.push(key);
+        self.last_toggles.retain(|&(toggle, _)| toggle != key);
+        self.last_toggles.push((key, self.elapsed_time));
+        true
+    }
+
+    /// Pause the game if it is running, resume it if it is paused.
+    fn toggle_pause(&mut self) {
+        if self.frozen {
+            self.unfreeze();
+        }
+        else {
+            self.freeze();
+        }
+    }
+
+    /// Let the keyboard control the other player when playing alone against the computer, handing the current player
+    /// over to the computer. Otherwise, nothing changes.
+    fn switch_controlled_side(&mut self) {
+        if Field::solo_index(&self.config.input_sources).is_none() {
+            return;
+        }
+
+        self.controlled_side = match self.controlled_side {
+            FieldSide::Left => FieldSide::Right,
+            FieldSide::Right => FieldSide::Left,
+        };
+        for player in &mut self.players {
+            player.set_movement(Movement::None);
+        }
+    }
+
+    /// Let the computer choose the movement of the player at `index` at the given skill `level`: move the handle
+    /// towards the ball's vertical position unless the ball is within the handle's dead zone.
+    fn ai_movement(&self, index: usize, level: AiLevel) -> Movement {
+        let handle: [f64; 4] = self.players[index].get_bounding_box();
+        let ball: [f64; 4] = self.ball.bounding_box();
+        let handle_height: f64 = handle[3] - handle[1];
+
+        // An easy opponent only reacts to an approaching ball and is satisfied with a rougher position.
+        let is_approaching: bool = (self.ball_speed().0 < 0.0) == (index == 0);
+        let dead_zone: f64 = match level {
+            AiLevel::Easy if !is_approaching => return Movement::None,
+            AiLevel::Easy => 0.4,
+            AiLevel::Hard => 0.1,
+        };
+        let tolerance: f64 = handle_height * self.config.ai_dead_zone.unwrap_or(dead_zone);
+
+        let offset: f64 = (ball[1] + ball[3]) / 2.0 - (handle[1] + handle[3]) / 2.0;
+        if offset > tolerance {
+            Movement::Down
+        }
+        else if offset < -tolerance {
+            Movement::Up
+        }
+        else {
+            Movement::None
+        }
+    }
+
+    /// Let the computer choose the movements of all players it controls. Between decisions, which are made in the
+    /// configured interval, the players keep their last movements. `dt` is the change in time since the last call.
+    fn apply_ai_movements(&mut self, dt: f64) {
+        for index in 0..self.players.len() {
+            if let InputSource::Ai(level) = self.input_source(index) {
+                self.ai_decision_countdowns[index] -= dt;
+                if self.ai_decision_countdowns[index] > 0.0 {
+                    continue;
+                }
+
+                self.ai_decision_countdowns[index] = self.config.ai_decision_interval;
+                let movement: Movement = self.ai_movement(index, level);
+                self.players[index].set_movement(movement);
+            }
+        }
+    }
+
+    /// Handle input events.
+    pub fn handle_input(&mut self, input: &Input) {
+        if let Input::Button(ref button_arguments) = *input {
+            match button_arguments.state {
+                ButtonState::Press => self.on_button_pressed(button_arguments.button),
+                ButtonState::Release => self.on_button_released(button_arguments.button),
+            }
+        }
+    }
+
     /// Handle button press events.
     pub fn on_button_pressed(&mut self, button: Button) {
         if let Button::Keyboard(key) = button {
-            match key {
-                Key::W => self.players[0].set_movement(Movement::Up),
-                Key::S => self.players[0].set_movement(Movement::Down),
-                Key::Up => self.players[1].set_movement(Movement::Up),
-                Key::Down => self.players[1].set_movement(Movement::Down),
-                _ => {},
+            // The pause key is not debounced by time since the time stands still while the game is paused.
+            if key == PAUSE_KEY {
+                if self.accept_toggle(key, 0.0) {
+                    self.toggle_pause();
+                }
+                return;
+            }
+
+            // While the game is paused or the match is over, only the pause key acts.
+            if !self.is_running() {
+                return;
+            }
+
+            // Aim the next serve while the ball waits to be served.
+            if self.config.aim_serves && self.serve_countdown > 0.0 {
+                match key {
+                    Key::Left => self.set_aim_angle(self.aim_angle - AIM_ANGLE_CHANGE),
+                    Key::Right => self.set_aim_angle(self.aim_angle + AIM_ANGLE_CHANGE),
+                    _ => {},
+                }
+            }
+
+            if key == SWITCH_SIDE_KEY {
+                if self.accept_toggle(key, self.config.toggle_debounce) {
+                    self.switch_controlled_side();
+                }
+                return;
+            }
+
+            for (player, controls) in self.keyboard_players() {
+                if key == controls.up {
+                    player.set_movement(Movement::Up);
+                }
+                else if key == controls.down {
+                    player.set_movement(Movement::Down);
+                }
             }
         }
     }
@@ -84,41 +622,101 @@ impl Field {
     /// Handle button release events.
     pub fn on_button_released(&mut self, button: Button) {
         if let Button::Keyboard(key) = button {
-            match key {
-                Key::W | Key::S => self.players[0].set_movement(Movement::None),
-                Key::Up | Key::Down => self.players[1].set_movement(Movement::None),
-                _ => {},
+            if key == SWITCH_SIDE_KEY || key == PAUSE_KEY {
+                self.held_toggles.retain(|&held| held != key);
+                return;
+            }
+
+            for (player, controls) in self.keyboard_players() {
+                if key == controls.up || key == controls.down {
+                    player.set_movement(Movement::None);
+                }
             }
         }
     }
 
-    /// Draw the field with its contents.
-    pub fn on_render(&self, context: Context, graphics: &mut G2d) {
-        let line_width: f64 = 1.0;
+    /// Draw the field with its contents. If a `pixel_scale` (device pixels per point) is given, all contents are
+    /// snapped to whole pixels.
+    pub fn on_render(&self, context: Context, graphics: &mut G2d, pixel_scale: Option<f64>) {
+        let snap = |x: f64, y: f64| -> (f64, f64) { pixels::snap_point((x, y), pixel_scale) };
+
+        // Tint the field after a point.
+        if self.flash.is_visible() {
+            let tint = Rectangle::new(self.flash.color());
+            tint.draw([0.0, 0.0, f64::from(self.width), f64::from(self.height)], &context.draw_state,
+                      context.transform, graphics);
+        }
 
         // Draw the center line.
-        let center_line = Line::new(color::GRAY, line_width);
-        let position_x: f64 = f64::from(self.width) / 2.0 - line_width;
+        let center_line = Line::new(self.config.center_line_color, LINE_WIDTH);
+        let position_x: f64 = f64::from(self.width) / 2.0 - LINE_WIDTH;
         let number_of_dashes: u32 = 10;
         let height: f64 = f64::from(self.height) / (f64::from(number_of_dashes) * 2.0 - 1.0);
         for i in 0..number_of_dashes {
-            let position_y: f64 = f64::from(i) * height * 2.0;
-            let transformation = context.transform.trans(position_x, position_y);
+            let position: (f64, f64) = snap(position_x, f64::from(i) * height * 2.0);
+            let transformation = context.transform.trans(position.0, position.1);
             center_line.draw([0.0, 0.0, 0.0, height], &context.draw_state, transformation, graphics);
         }
 
         // Draw the top line.
-        let line = Line::new(color::WHITE, line_width);
-        let transformation = context.transform.trans(0.0, 0.0 + line_width);
+        let line = Line::new(self.config.border_color, LINE_WIDTH);
+        let position: (f64, f64) = snap(0.0, LINE_WIDTH);
+        let transformation = context.transform.trans(position.0, position.1);
         line.draw([0.0, 0.0, f64::from(self.width), 0.0], &context.draw_state, transformation, graphics);
 
+        // Draw the target zones on the edge opposite the controlled player.
+        let target = Line::new(self.config.border_color, LINE_WIDTH * 2.0);
+        let target_x: f64 = match self.controlled_side {
+            FieldSide::Left => f64::from(self.width) - LINE_WIDTH,
+            FieldSide::Right => LINE_WIDTH,
+        };
+        for &(top_y, bottom_y, _) in &self.config.targets {
+            let position: (f64, f64) = snap(target_x, top_y);
+            let transformation = context.transform.trans(position.0, position.1);
+            target.draw([0.0, 0.0, 0.0, bottom_y - top_y], &context.draw_state, transformation, graphics);
+        }
+
+        // Draw the practice walls on the edges while they still have saves remaining.
+        let wall = Line::new(self.config.center_line_color, LINE_WIDTH);
+        let wall_xs: [f64; 2] = [LINE_WIDTH, f64::from(self.width) - LINE_WIDTH];
+        for (&saves, &position_x) in self.saves_remaining.iter().zip(&wall_xs) {
+            if saves > 0 {
+                let position: (f64, f64) = snap(position_x, 0.0);
+                let transformation = context.transform.trans(position.0, position.1);
+                wall.draw([0.0, 0.0, 0.0, f64::from(self.height)], &context.draw_state, transformation, graphics);
+            }
+        }
+
         // Draw the players.
         for player in &self.players {
-            player.draw(&context, graphics);
+            player.draw(&context, graphics, pixel_scale);
+        }
+
+        // Draw the preview of an aimed serve as a dotted line in the direction of the serve.
+        if self.config.aim_serves && self.serve_countdown > 0.0 {
+            let dot = Ellipse::new(self.config.center_line_color);
+            let dot_size: f64 = 2.0;
+            let ball: [f64; 4] = self.ball.bounding_box();
+            let center: (f64, f64) = ((ball[0] + ball[2]) / 2.0, (ball[1] + ball[3]) / 2.0);
+            let velocity: (f64, f64) = self.serve_velocity();
+            let speed: f64 = velocity.0.hypot(velocity.1);
+            if speed > 0.0 {
+                let number_of_dots: u32 = 10;
+                let distance: f64 = 15.0;
+                for i in 1..(number_of_dots + 1) {
+                    let offset: f64 = f64::from(i) * distance / speed;
+                    let position: (f64, f64) = snap(center.0 + velocity.0 * offset - dot_size / 2.0,
+                                                    center.1 + velocity.1 * offset - dot_size / 2.0);
+                    let transformation = context.transform.trans(position.0, position.1);
+                    dot.draw([0.0, 0.0, dot_size, dot_size], &context.draw_state, transformation, graphics);
+                }
+            }
         }
 
         // Draw the ball.
-        self.ball.draw(&context, graphics);
+        let left: [f64; 4] = self.players[0].get_bounding_box();
+        let right: [f64; 4] = self.players[1].get_bounding_box();
+        self.ball.draw(&context, graphics, [left[2], right[0]], pixel_scale);
     }
 
     /// Resize the field.
@@ -130,74 +728,1942 @@ impl Field {
         }
     }
 
+    /// Handle a `touch` at the given `position` (`[x, y]`) on the field: while a finger touches a half of the field,
+    /// the handle of the player on that half is centered on the finger. Handles controlled by the computer cannot be
+    /// touched, nor can handles locked while the ball waits to be served.
+    pub fn on_touch(&mut self, position: [f64; 2], touch: Touch) {
+        if !self.is_running() || (touch != Touch::Start && touch != Touch::Move) {
+            return;
+        }
+
+        if self.config.lock_paddles_on_serve && self.serve_countdown > 0.0 {
+            return;
+        }
+
+        let index: usize = Field::touched_player(position[0], self.width);
+        if self.input_source(index) != InputSource::Keyboard {
+            return;
+        }
+
+        let handle: [f64; 4] = self.players[index].get_bounding_box();
+        self.players[index].set_position_y(position[1] - (handle[3] - handle[1]) / 2.0, self.height);
+    }
+
+    /// Get the index of the player on the half of a field with the given `width` that contains the horizontal
+    /// position `x`.
+    fn touched_player(x: f64, width: u32) -> usize {
+        if x < f64::from(width) / 2.0 {
+            0
+        }
+        else {
+            1
+        }
+    }
+
     /// Update the field state.
-    pub fn on_update(&mut self, update_arguments: &UpdateArgs) {
-        let dt: f64 = update_arguments.dt;
+    ///
+    /// Returns the status of the ball, telling whether a point has been scored.
+    pub fn on_update(&mut self, update_arguments: &UpdateArgs) -> BallStatus {
+        self.step(update_arguments.dt)
+    }
 
-        // Update the speeds if necessary.
-        self.last_speed_change += dt;
-        if self.last_speed_change >= SPEED_CHANGE_INTERVAL {
-            self.last_speed_change = 0.0;
+    /// Load the key bindings from the file at `path`. Bindings missing in the file are taken from the configured
+    /// preset.
+    ///
+    /// If the file cannot be loaded, the current key bindings are kept and an error is returned.
+    pub fn reload_controls(&mut self, path: &Path) -> Result<()> {
+        let controls: Controls = Controls::load(path, self.config.controls_preset.to_controls())?;
+        self.set_controls(controls);
+        Ok(())
+    }
 
-            self.ball.change_speed(SPEED_CHANGE);
-            for player in &mut self.players {
-                player.change_speed(SPEED_CHANGE);
+    /// Get the index of the player on the given `side` of the field.
+    fn player_index(side: FieldSide) -> usize {
+        match side {
+            FieldSide::Left => 0,
+            FieldSide::Right => 1,
+        }
+    }
+
+    /// Push overlapping handles of the given `players` apart so they touch instead of overlapping. The handles are
+    /// separated along the axis on which they overlap least, each moving by half of the overlap, but not beyond the
+    /// top or bottom of a field with the given `height`.
+    fn resolve_paddle_overlap(players: &mut [Player], height: u32) {
+        for first in 0..players.len() {
+            for second in (first + 1)..players.len() {
+                let a: [f64; 4] = players[first].get_bounding_box();
+                let b: [f64; 4] = players[second].get_bounding_box();
+
+                let overlap_x: f64 = a[2].min(b[2]) - a[0].max(b[0]);
+                let overlap_y: f64 = a[3].min(b[3]) - a[1].max(b[1]);
+                if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                    continue;
+                }
+
+                // Push the handles away from each other, the first one towards its side of the other one.
+                let offset: (f64, f64) = if overlap_x < overlap_y {
+                    let direction: f64 = if a[0] <= b[0] { -1.0 } else { 1.0 };
+                    (direction * overlap_x / 2.0, 0.0)
+                }
+                else {
+                    let direction: f64 = if a[1] <= b[1] { -1.0 } else { 1.0 };
+                    (0.0, direction * overlap_y / 2.0)
+                };
+                players[first].shift(offset);
+                players[second].shift((-offset.0, -offset.1));
+                for &index in &[first, second] {
+                    let top: f64 = players[index].get_bounding_box()[1];
+                    players[index].set_position_y(top, height);
+                }
+            }
+        }
+    }
+
+    /// Reset the simulation state to the given `snapshot`.
+    #[allow(dead_code)]  // Not used until rollback networking is implemented.
+    pub fn restore(&mut self, snapshot: FieldSnapshot) {
+        self.ai_decision_countdowns = snapshot.ai_decision_countdowns;
+        self.aim_angle = snapshot.aim_angle;
+        self.ball = snapshot.ball;
+        self.elapsed_time = snapshot.elapsed_time;
+        self.flash = snapshot.flash;
+        self.last_speed_change = snapshot.last_speed_change;
+        self.players = snapshot.players;
+        self.rally_time = snapshot.rally_time;
+        self.rng = snapshot.rng;
+        self.saves_remaining = snapshot.saves_remaining;
+        self.serve_countdown = snapshot.serve_countdown;
+        self.speed_burst_timer = snapshot.speed_burst_timer;
+        self.state = self.game_state();
+        self.stats = snapshot.stats;
+    }
+
+    /// Compute the velocity of the ball when it is served: if serves are aimed, the ball's speed is kept, but its
+    /// direction is given by the aim angle, towards the same side as the current direction. Otherwise, the ball's
+    /// current velocity is used.
+    fn serve_velocity(&self) -> (f64, f64) {
+        let speed: (f64, f64) = self.ball.get_speed();
+        if !self.config.aim_serves {
+            return speed;
+        }
+
+        let total_speed: f64 = speed.0.hypot(speed.1);
+        (total_speed * self.aim_angle.cos() * speed.0.signum(), total_speed * self.aim_angle.sin())
+    }
+
+    /// Set the angle of the next serve, limited to the range from `-MAXIMUM_AIM_ANGLE` to `MAXIMUM_AIM_ANGLE`.
+    fn set_aim_angle(&mut self, angle: f64) {
+        self.aim_angle = angle.clamp(-MAXIMUM_AIM_ANGLE, MAXIMUM_AIM_ANGLE);
+    }
+
+    /// Set a function called with the side and the new score of a player whenever the player's score changes due to
+    /// the ball leaving the field.
+    #[allow(dead_code)]  // Not used until integrations register hooks.
+    pub fn set_score_hook<F: FnMut(FieldSide, isize) + 'static>(&mut self, hook: F) {
+        self.score_hook = Some(Box::new(hook));
+    }
+
+    /// Set the key bindings for the players.
+    pub fn set_controls(&mut self, controls: Controls) {
+        self.controls = controls;
+    }
+
+    /// Capture the current simulation state so the field can be restored to it later on.
+    ///
+    /// The field's size and settings are not part of the snapshot.
+    #[allow(dead_code)]  // Not used until rollback networking is implemented.
+    pub fn snapshot(&self) -> FieldSnapshot {
+        FieldSnapshot {
+            ai_decision_countdowns: self.ai_decision_countdowns,
+            aim_angle: self.aim_angle,
+            ball: self.ball,
+            elapsed_time: self.elapsed_time,
+            flash: self.flash,
+            last_speed_change: self.last_speed_change,
+            players: self.players,
+            rally_time: self.rally_time,
+            rng: self.rng,
+            saves_remaining: self.saves_remaining,
+            serve_countdown: self.serve_countdown,
+            speed_burst_timer: self.speed_burst_timer,
+            stats: self.stats,
+        }
+    }
+
+    /// Advance the simulation of the field by the change in time `dt`, independent of any rendering or windowing.
+    ///
+    /// Returns the status of the ball, telling whether a point has been scored.
+    pub fn step(&mut self, dt: f64) -> BallStatus {
+        if !self.is_running() {
+            return BallStatus::WithinGame;
+        }
+
+        self.elapsed_time += dt;
+        self.flash.update(dt);
+        self.apply_ai_movements(dt);
+
+        // Until the ball is served, only the players can move (unless they are locked, too). The speeds are not
+        // changed either, so the difficulty does not jump as soon as the ball is served.
+        if !self.is_active() {
+            self.serve_countdown = (self.serve_countdown - dt).max(0.0);
+            if !self.config.lock_paddles_on_serve {
+                self.players[0].update(dt, self.height);
+                self.players[1].update(dt, self.height);
+            }
+
+            // Serve the ball in the aimed direction.
+            if self.serve_countdown == 0.0 {
+                let velocity: (f64, f64) = self.serve_velocity();
+                self.ball.set_speed(velocity);
+            }
+
+            return BallStatus::WithinGame;
+        }
+
+        self.rally_time += dt;
+
+        // Update the speeds if necessary. In skill mode, the speed only changes when the ball is returned.
+        if self.config.difficulty_mode == DifficultyMode::Ramp {
+            self.last_speed_change += dt;
+            if self.last_speed_change >= SPEED_CHANGE_INTERVAL {
+                self.last_speed_change = 0.0;
+
+                self.ball.change_speed(SPEED_CHANGE);
+                for player in &mut self.players {
+                    player.change_speed(SPEED_CHANGE);
+                }
             }
         }
 
+        let previous_handles = [
+            self.players[0].get_bounding_box(),
+            self.players[1].get_bounding_box(),
+        ];
+
         self.players[0].update(dt, self.height);
         self.players[1].update(dt, self.height);
+        if self.config.paddle_collision {
+            Field::resolve_paddle_overlap(&mut self.players, self.height);
+        }
+
+        self.update_speed_burst(dt);
 
         let player_handles = [
             self.players[0].get_bounding_box(),
             self.players[1].get_bounding_box(),
         ];
 
-        let status: BallStatus = self.ball.update(dt, self.width, self.height, &player_handles);
+        // Handles moving into the ball push it away, even if they moved past it within this step.
+        let hits: usize = self.ball.get_hits();
+        for (index, (previous, current)) in previous_handles.iter().zip(player_handles.iter()).enumerate() {
+            let _ = self.ball.collide_with_moving(index, *previous, *current, self.height, self.top_inset());
+        }
+
+        let mut status: BallStatus = self.ball.update(dt, self.width, self.height, self.top_inset(), &player_handles);
+        status = self.use_save(status);
+        if self.config.difficulty_mode == DifficultyMode::Skill && self.ball.get_hits() > hits {
+            self.ball.change_speed(SPEED_CHANGE);
+        }
+        if status == BallStatus::WithinGame && self.is_rally_capped() {
+            status = BallStatus::Let;
+        }
         self.update_scores(status);
+        status
     }
 
-    /// If the ball left the field on the left or right side, the other side's player will get a point.
-    fn update_scores(&mut self, status: BallStatus) {
-        match status {
-            BallStatus::WithinGame => return,
-            BallStatus::LeftOnLeftSide => {
-                self.players[1].update_score(1);
-            },
-            BallStatus::LeftOnRightSide => {
-                self.players[0].update_score(1);
-            }
+    /// Get the number of misses the practice wall behind the handle of the player at `index` returns per game: only
+    /// players controlled with the keyboard get a wall.
+    fn initial_saves(config: &Config, index: usize) -> usize {
+        match config.input_sources[index] {
+            InputSource::Keyboard => config.practice_saves,
+            _ => 0,
+        }
+    }
+
+    /// If the ball is about to leave the field on a side whose practice wall still has saves remaining, send the ball
+    /// back into the field instead, using up one save.
+    ///
+    /// Returns the status of the ball after the practice walls had their chance.
+    fn use_save(&mut self, status: BallStatus) -> BallStatus {
+        let side: FieldSide = match status {
+            BallStatus::LeftOnLeftSide => FieldSide::Left,
+            BallStatus::LeftOnRightSide => FieldSide::Right,
+            _ => return status,
+        };
+
+        let index: usize = Field::player_index(side);
+        if self.saves_remaining[index] == 0 {
+            return status;
         }
 
-        // The ball left the field. Create a new one.
-        self.ball = Ball::new([self.width, self.height]);
+        self.saves_remaining[index] -= 1;
+        self.ball.bounce_back(side);
+        BallStatus::WithinGame
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Determine if the current rally reached the configured limit of its length.
+    fn is_rally_capped(&self) -> bool {
+        match self.config.rally_cap {
+            Some(RallyCap::Hits(hits)) => self.ball.get_hits() >= hits,
+            Some(RallyCap::Seconds(seconds)) => self.rally_time >= seconds,
+            None => false,
+        }
+    }
 
-    #[test]
-    fn new() {
-        let field = Field::new([200, 100]);
-        assert!(field.last_speed_change <= 0.0);  // The first speed change might take longer to happen.
-        assert_eq!(field.width, 200);
-        assert_eq!(field.height, 100);
+    /// Summarize the current state of the game in a human-readable form, e.g. for bug reports.
+    pub fn summary(&self) -> String {
+        let scores: [isize; 2] = self.get_player_scores();
+        let ball: [f64; 4] = self.ball.bounding_box();
+        let speed: (f64, f64) = self.ball_speed();
+        let left: [f64; 4] = self.players[0].get_bounding_box();
+        let right: [f64; 4] = self.players[1].get_bounding_box();
+
+        format!("Field: {width}x{height}\n\
+                 Seed: {seed}\n\
+                 Scores: {left_score} - {right_score}\n\
+                 Ball: position ({ball_x:.2}, {ball_y:.2}), speed ({speed_x:.2}, {speed_y:.2}), \
+                 {speed:.2} in total\n\
+                 Players: left ({left_x:.2}, {left_y:.2}), right ({right_x:.2}, {right_y:.2})\n\
+                 Last speed change: {last_speed_change:.2}s ago\n\
+                 Elapsed time: {elapsed_time:.2}s",
+                width = self.width, height = self.height, seed = self.seed,
+                left_score = scores[0], right_score = scores[1],
+                ball_x = ball[0], ball_y = ball[1], speed_x = speed.0, speed_y = speed.1,
+                speed = self.ball_speed_magnitude(),
+                left_x = left[0], left_y = left[1], right_x = right[0], right_y = right[1],
+                last_speed_change = self.last_speed_change, elapsed_time = self.elapsed_seconds())
     }
 
-    #[test]
-    fn get_player_scores() {
-        let mut field = Field::new([200, 100]);
-        field.players[0].update_score(42);
-        field.players[1].update_score(-42);
-        let scores: [isize; 2] = field.get_player_scores();
-        assert_eq!(scores, [42, -42]);
+    /// Advance the timer of the ball's speed bursts by `dt`: after each interval, the ball's speed is multiplied for
+    /// the duration of the burst.
+    fn update_speed_burst(&mut self, dt: f64) {
+        let burst: SpeedBurst = match self.config.speed_burst {
+            Some(burst) => burst,
+            None => return,
+        };
+
+        self.speed_burst_timer += dt;
+        if self.speed_burst_timer >= burst.interval + burst.duration {
+            self.speed_burst_timer = 0.0;
+        }
+
+        let multiplier: f64 = if self.speed_burst_timer >= burst.interval { burst.multiplier } else { 1.0 };
+        self.ball.set_speed_multiplier(multiplier);
+    }
+
+    /// If the ball left the field on the left or right side, the other side's player will get a point, plus the bonus
+    /// points of the target zone the ball left the field in (if any).
+    ///
+    /// With own goals, a player whose handle last touched the ball before it left the field on that player's side
+    /// loses a point instead.
+    fn update_scores(&mut self, status: BallStatus) {
+        if status != BallStatus::WithinGame {
+            self.stats.longest_rally = self.stats.longest_rally.max(self.ball.get_hits());
+        }
+
+        let (conceding_side, scoring_side): (FieldSide, FieldSide) = match status {
+            BallStatus::WithinGame => return,
+            BallStatus::Let => {
+                // No one scores, the rally is simply replayed.
+                self.serve_new_ball(None);
+                return;
+            },
+            BallStatus::LeftOnLeftSide => (FieldSide::Left, FieldSide::Right),
+            BallStatus::LeftOnRightSide => (FieldSide::Right, FieldSide::Left),
+        };
+
+        if self.ball.get_hits() < self.config.min_rally_to_score {
+            // The rally was too short to count, e.g. while warming up.
+            self.serve_new_ball(Some(conceding_side));
+            return;
+        }
+
+        let scoring_index: usize = Field::player_index(scoring_side);
+        if let Some(colors) = self.config.point_flash_colors {
+            self.flash.start(colors[scoring_index]);
+        }
+
+        let is_own_goal: bool = self.ball.get_last_collision() == Some(Field::player_index(conceding_side));
+        let changed_side: FieldSide = if self.config.scoring_rule == ScoringRule::OwnGoals && is_own_goal {
+            self.add_player_score(conceding_side, -1);
+            conceding_side
+        }
+        else {
+            // The targets are only on the edge opposite the controlled player, so only they can hit them.
+            let ball: [f64; 4] = self.ball.bounding_box();
+            let ball_y: f64 = (ball[1] + ball[3]) / 2.0;
+            let bonus: isize = if scoring_side == self.controlled_side {
+                self.config.targets.iter()
+                    .find(|&&(top_y, bottom_y, _)| top_y <= ball_y && ball_y <= bottom_y)
+                    .map_or(0, |&(_, _, bonus)| bonus)
+            }
+            else {
+                0
+            };
+
+            self.add_player_score(scoring_side, 1);
+            self.add_player_score(scoring_side, bonus);
+
+            // Only points actually won count for the statistics, not those lost by an own goal.
+            self.stats.points[scoring_index] += 1;
+            if self.ball.get_hits() == 0 {
+                self.stats.aces[scoring_index] += 1;
+            }
+            scoring_side
+        };
+
+        let score: isize = self.players[Field::player_index(changed_side)].get_score();
+        if let Some(ref mut hook) = self.score_hook {
+            hook(changed_side, score);
+        }
+
+        if self.config.handicap {
+            self.apply_handicap();
+        }
+
+        self.state = self.game_state();
+        self.serve_new_ball(Some(conceding_side));
+    }
+
+    /// Replace the ball that left the field with a new one and wait before serving it. If serves are directed, the
+    /// ball is served `towards` the given side.
+    fn serve_new_ball(&mut self, towards: Option<FieldSide>) {
+        if self.config.recenter_paddles_on_point {
+            for player in &mut self.players {
+                player.move_to_center(self.height);
+            }
+        }
+
+        self.ball = Field::create_ball([self.width, self.height], &self.config, &mut self.rng, towards);
+        self.rally_time = 0.0;
+        self.serve_countdown = self.config.serve_delay;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+    use std::env;
+    use std::fs;
+    use std::fs::File;
+    use std::io::Write;
+    use std::path::PathBuf;
+    use std::rc::Rc;
+    use piston_window::ButtonArgs;
+    use color;
+    use config::DEFAULT_HANDLE_SIZE;
+    use controls;
+    use controls::ControlsPreset;
+    use controls::PlayerControls;
+    use super::*;
+
+    #[test]
+    fn new() {
+        let field = Field::new([200, 100], &Config::default());
+        assert!(field.last_speed_change <= 0.0);  // The first speed change might take longer to happen.
+        assert_eq!(field.width, 200);
+        assert_eq!(field.height, 100);
+
+        // The players start at the vertical center.
+        for player in &field.players {
+            let bounding_box = player.get_bounding_box();
+            assert_eq!((bounding_box[1] + bounding_box[3]) / 2.0, 50.0);
+        }
+    }
+
+    #[test]
+    fn new_handle_sizes() {
+        let config = Config { handle_sizes: [(10.0, 60.0), (20.0, 80.0)], ..Config::default() };
+        let field = Field::new([200, 100], &config);
+        assert_eq!(field.players[0].get_bounding_box(), [10.0, 20.0, 20.0, 80.0]);
+        assert_eq!(field.players[1].get_bounding_box(), [170.0, 10.0, 190.0, 90.0]);
+    }
+
+    #[test]
+    fn new_line_colors() {
+        let config = Config {
+            border_color: color::GRAY,
+            center_line_color: color::WHITE,
+            ..Config::default()
+        };
+        let field = Field::new([200, 100], &config);
+        assert_eq!(field.config.border_color, color::GRAY);
+        assert_eq!(field.config.center_line_color, color::WHITE);
+    }
+
+    #[test]
+    fn add_player_score() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.add_player_score(FieldSide::Left, 3);
+        assert_eq!(field.get_player_scores(), [3, 0]);
+
+        field.add_player_score(FieldSide::Right, -2);
+        assert_eq!(field.get_player_scores(), [3, -2]);
+    }
+
+    #[test]
+    fn get_player_scores() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.players[0].update_score(42);
+        field.players[1].update_score(-42);
+        let scores: [isize; 2] = field.get_player_scores();
+        assert_eq!(scores, [42, -42]);
+    }
+
+    #[test]
+    fn get_player_scores_starting_scores() {
+        let config = Config { starting_scores: [3, 0], ..Config::default() };
+        let field = Field::new([200, 100], &config);
+        assert_eq!(field.get_player_scores(), [3, 0]);
+    }
+
+    #[test]
+    fn handle_input_button() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let mut input = Input::Button(ButtonArgs {
+            state: ButtonState::Press,
+            button: Button::Keyboard(Key::Up),
+            scancode: None,
+        });
+
+        field.handle_input(&input);
+        assert_eq!(field.players[0].get_movement(), Movement::None);
+        assert_eq!(field.players[1].get_movement(), Movement::Up);
+
+        if let Input::Button(ref mut button_arguments) = input {
+            button_arguments.state = ButtonState::Release;
+        }
+        field.handle_input(&input);
+        assert_eq!(field.players[1].get_movement(), Movement::None);
+    }
+
+    #[test]
+    fn on_touch() {
+        let mut field = Field::new([200, 100], &Config::default());
+
+        // The left half belongs to the left player, the handle is centered on the finger.
+        field.on_touch([50.0, 40.0], Touch::Start);
+        assert_eq!(field.players[0].get_bounding_box()[1], 10.0);
+        assert_eq!(field.players[1].get_bounding_box()[1], 20.0);
+
+        // The right half belongs to the right player, the handle stays within the field.
+        field.on_touch([100.0, 5.0], Touch::Move);
+        assert_eq!(field.players[1].get_bounding_box()[1], 0.0);
+        field.on_touch([199.0, 95.0], Touch::Move);
+        assert_eq!(field.players[1].get_bounding_box()[1], 40.0);
+        assert_eq!(field.players[0].get_bounding_box()[1], 10.0);
+
+        // Lifting the finger does not move the handle.
+        field.on_touch([50.0, 90.0], Touch::End);
+        assert_eq!(field.players[0].get_bounding_box()[1], 10.0);
+    }
+
+    #[test]
+    fn on_touch_locked_serve() {
+        let config = Config { first_serve_delay: 0.2, lock_paddles_on_serve: true, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        // While the ball waits to be served, the locked handles cannot be touched.
+        field.on_touch([50.0, 10.0], Touch::Start);
+        assert_eq!(field.players[0].get_bounding_box()[1], 20.0);
+
+        // After the serve, they can be touched again.
+        assert_eq!(field.step(0.2), BallStatus::WithinGame);
+        field.on_touch([50.0, 10.0], Touch::Move);
+        assert_eq!(field.players[0].get_bounding_box()[1], 0.0);
+    }
+
+    #[test]
+    fn on_touch_computer_player() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.on_touch([150.0, 10.0], Touch::Start);
+        assert_eq!(field.players[1].get_bounding_box()[1], 20.0);
+    }
+
+    /// Get the angle between the ball's direction and the horizontal when it is served in `field`.
+    fn serve_angle(field: &Field) -> f64 {
+        let velocity: (f64, f64) = field.serve_velocity();
+        velocity.1.atan2(velocity.0.abs())
+    }
+
+    #[test]
+    fn on_button_pressed_aim() {
+        let config = Config { aim_serves: true, first_serve_delay: 3.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.ball.set_speed((-30.0, 40.0));
+        assert!(serve_angle(&field).abs() < 1e-9);
+
+        field.on_button_pressed(Button::Keyboard(Key::Right));
+        assert!((serve_angle(&field) - AIM_ANGLE_CHANGE).abs() < 1e-9);
+
+        field.on_button_pressed(Button::Keyboard(Key::Left));
+        field.on_button_pressed(Button::Keyboard(Key::Left));
+        assert!((serve_angle(&field) + AIM_ANGLE_CHANGE).abs() < 1e-9);
+
+        // The direction towards the left side and the total speed are kept.
+        let velocity: (f64, f64) = field.serve_velocity();
+        assert!(velocity.0 < 0.0);
+        assert!((velocity.0.hypot(velocity.1) - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn on_button_pressed_aim_range() {
+        let config = Config { aim_serves: true, first_serve_delay: 3.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        for _ in 0..100 {
+            field.on_button_pressed(Button::Keyboard(Key::Right));
+        }
+        assert!((serve_angle(&field) - MAXIMUM_AIM_ANGLE).abs() < 1e-9);
+
+        for _ in 0..200 {
+            field.on_button_pressed(Button::Keyboard(Key::Left));
+        }
+        assert!((serve_angle(&field) + MAXIMUM_AIM_ANGLE).abs() < 1e-9);
+    }
+
+    #[test]
+    fn on_button_pressed_aim_after_serve() {
+        let config = Config { aim_serves: true, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.on_button_pressed(Button::Keyboard(Key::Right));
+        assert_eq!(field.aim_angle, 0.0);
+    }
+
+    #[test]
+    fn on_button_pressed_aim_disabled() {
+        let config = Config { first_serve_delay: 3.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let speed: (f64, f64) = field.ball.get_speed();
+        field.on_button_pressed(Button::Keyboard(Key::Right));
+        assert_eq!(field.aim_angle, 0.0);
+        assert_eq!(field.serve_velocity(), speed);
+    }
+
+    #[test]
+    fn on_button_pressed_custom_controls() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let mut controls = Controls::default();
+        controls.players[0] = PlayerControls { down: Key::K, up: Key::I };
+        field.set_controls(controls);
+
+        field.on_button_pressed(Button::Keyboard(Key::W));
+        assert_eq!(field.players[0].get_movement(), Movement::None);
+
+        field.on_button_pressed(Button::Keyboard(Key::K));
+        assert_eq!(field.players[0].get_movement(), Movement::Down);
+
+        field.on_button_released(Button::Keyboard(Key::K));
+        assert_eq!(field.players[0].get_movement(), Movement::None);
+    }
+
+    #[test]
+    fn new_controls_preset() {
+        let config = Config { controls_preset: ControlsPreset::SideBySide, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        field.on_button_pressed(Button::Keyboard(Key::I));
+        assert_eq!(field.players[1].get_movement(), Movement::Up);
+
+        field.on_button_pressed(Button::Keyboard(Key::S));
+        assert_eq!(field.players[0].get_movement(), Movement::Down);
+    }
+
+    #[test]
+    fn reload_controls() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let valid: PathBuf = env::temp_dir().join(format!("mief-field-valid-{}.toml", ::std::process::id()));
+        let invalid: PathBuf = env::temp_dir().join(format!("mief-field-invalid-{}.toml", ::std::process::id()));
+        File::create(&valid).unwrap().write_all(b"[left]\nup = \"I\"\n").unwrap();
+        File::create(&invalid).unwrap().write_all(b"[left]\nup = \"Foo\"\n").unwrap();
+
+        let valid_result = field.reload_controls(&valid);
+        let controls: Controls = field.controls;
+        let invalid_result = field.reload_controls(&invalid);
+        fs::remove_file(&valid).unwrap();
+        fs::remove_file(&invalid).unwrap();
+
+        // Loading the valid file changes the bindings.
+        assert!(valid_result.is_ok());
+        assert_eq!(controls.players[0].up, Key::I);
+
+        // Loading the invalid file keeps the previous bindings.
+        assert!(invalid_result.is_err());
+        assert_eq!(field.controls, controls);
+    }
+
+    #[test]
+    fn new_seeded() {
+        let config = Config { seed: Some(42), ..Config::default() };
+        let field = Field::new([200, 100], &config);
+        let other_field = Field::new([200, 100], &config);
+        assert_eq!(field.get_seed(), 42);
+        assert_eq!(field.ball.get_speed(), other_field.ball.get_speed());
+    }
+
+    /// Create a field with the ball at its vertical center (`y = 50`) moving to the right and the handle of the player
+    /// at `index` shifted vertically by `offset` from the center.
+    fn ai_field(index: usize, offset: f64) -> Field {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.ball.set_speed((100.0, 0.0));
+        field.players[index].shift((0.0, offset));
+        field
+    }
+
+    #[test]
+    fn ai_movement() {
+        for &(offset, movement) in &[(-30.0, Movement::Down), (30.0, Movement::Up), (3.0, Movement::None)] {
+            let field = ai_field(1, offset);
+            assert_eq!(field.ai_movement(1, AiLevel::Hard), movement);
+        }
+
+        // An easy opponent tolerates larger offsets.
+        let field = ai_field(1, -10.0);
+        assert_eq!(field.ai_movement(1, AiLevel::Hard), Movement::Down);
+        assert_eq!(field.ai_movement(1, AiLevel::Easy), Movement::None);
+
+        let field = ai_field(1, -30.0);
+        assert_eq!(field.ai_movement(1, AiLevel::Easy), Movement::Down);
+    }
+
+    #[test]
+    fn ai_movement_dead_zone() {
+        let config = Config { ai_dead_zone: Some(0.5), ..Config::default() };
+        for &(offset, movement) in &[(-31.0, Movement::Down), (31.0, Movement::Up), (-29.0, Movement::None),
+                                     (29.0, Movement::None)] {
+            let mut field = Field::new([200, 100], &config);
+            field.ball.set_speed((100.0, 0.0));
+            field.players[1].shift((0.0, offset));
+            assert_eq!(field.ai_movement(1, AiLevel::Hard), movement);
+            assert_eq!(field.ai_movement(1, AiLevel::Easy), movement);
+        }
+    }
+
+    #[test]
+    fn ai_movement_easy_ball_moving_away() {
+        let field = ai_field(0, -30.0);
+        assert_eq!(field.ai_movement(0, AiLevel::Easy), Movement::None);
+        assert_eq!(field.ai_movement(0, AiLevel::Hard), Movement::Down);
+    }
+
+    #[test]
+    fn step_input_sources() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.players[1].shift((0.0, -30.0));
+
+        // The keyboard moves the left player only, the computer moves the right player towards the ball.
+        field.on_button_pressed(Button::Keyboard(Key::W));
+        field.on_button_pressed(Button::Keyboard(Key::Up));
+        let _ = field.step(0.0);
+        assert_eq!(field.players[0].get_movement(), Movement::Up);
+        assert_eq!(field.players[1].get_movement(), Movement::Down);
+
+        field.on_button_released(Button::Keyboard(Key::W));
+        field.on_button_released(Button::Keyboard(Key::Down));
+        let _ = field.step(0.0);
+        assert_eq!(field.players[0].get_movement(), Movement::None);
+        assert_eq!(field.players[1].get_movement(), Movement::Down);
+    }
+
+    #[test]
+    fn step_ai_decision_interval() {
+        let config = Config { ai_decision_interval: 0.1,
+                              input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.ball.set_speed((100.0, 0.0));
+        field.players[1].shift((0.0, -30.0));
+        let _ = field.step(0.0);
+        assert_eq!(field.players[1].get_movement(), Movement::Down);
+
+        // Within the interval, the computer keeps its decision even though the handle is now below the ball.
+        field.players[1].shift((0.0, 60.0));
+        let _ = field.step(0.05);
+        assert_eq!(field.players[1].get_movement(), Movement::Down);
+
+        // After the interval, the computer reconsiders.
+        let _ = field.step(0.05);
+        assert_eq!(field.players[1].get_movement(), Movement::Up);
+    }
+
+    #[test]
+    fn switch_controlled_side() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        assert_eq!(field.controlled_side, FieldSide::Left);
+        field.players[0].shift((0.0, -30.0));
+
+        // After switching, the keys of the left player move the right player, the computer moves the left player.
+        field.on_button_pressed(Button::Keyboard(Key::Tab));
+        assert_eq!(field.controlled_side, FieldSide::Right);
+        field.on_button_pressed(Button::Keyboard(Key::W));
+        let _ = field.step(0.0);
+        assert_eq!(field.players[0].get_movement(), Movement::Down);
+        assert_eq!(field.players[1].get_movement(), Movement::Up);
+
+        field.on_button_released(Button::Keyboard(Key::W));
+        assert_eq!(field.players[1].get_movement(), Movement::None);
+
+        // Switching back returns the keys to the left player.
+        field.on_button_released(Button::Keyboard(Key::Tab));
+        let _ = field.step(config.toggle_debounce);
+        field.on_button_pressed(Button::Keyboard(Key::Tab));
+        assert_eq!(field.controlled_side, FieldSide::Left);
+        field.on_button_pressed(Button::Keyboard(Key::S));
+        assert_eq!(field.players[0].get_movement(), Movement::Down);
+        assert_eq!(field.players[1].get_movement(), Movement::None);
+    }
+
+    #[test]
+    fn switch_controlled_side_debounced() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        // Repeated presses while the key is held down only switch once.
+        for _ in 0..3 {
+            field.on_button_pressed(Button::Keyboard(Key::Tab));
+        }
+        assert_eq!(field.controlled_side, FieldSide::Right);
+
+        // Presses bunched up right after the switch are ignored, even if the key has been released in between.
+        for _ in 0..3 {
+            field.on_button_released(Button::Keyboard(Key::Tab));
+            let _ = field.step(0.01);
+            field.on_button_pressed(Button::Keyboard(Key::Tab));
+        }
+        assert_eq!(field.controlled_side, FieldSide::Right);
+
+        // After the debounce time, the key switches again.
+        field.on_button_released(Button::Keyboard(Key::Tab));
+        let _ = field.step(config.toggle_debounce);
+        field.on_button_pressed(Button::Keyboard(Key::Tab));
+        assert_eq!(field.controlled_side, FieldSide::Left);
+    }
+
+    #[test]
+    fn switch_controlled_side_two_players() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.on_button_pressed(Button::Keyboard(Key::Tab));
+        assert_eq!(field.controlled_side, FieldSide::Left);
+
+        field.on_button_pressed(Button::Keyboard(Key::W));
+        field.on_button_pressed(Button::Keyboard(Key::Down));
+        assert_eq!(field.players[0].get_movement(), Movement::Up);
+        assert_eq!(field.players[1].get_movement(), Movement::Down);
+    }
+
+    #[test]
+    fn snapshot_restore() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.players[0].set_movement(Movement::Down);
+        let snapshot: FieldSnapshot = field.snapshot();
+
+        field.players[1].update_score(42);
+        field.players[1].set_movement(Movement::Down);
+        for _ in 0..10 {
+            let _ = field.step(0.01);
+        }
+        assert_ne!(field.snapshot(), snapshot);
+
+        field.restore(snapshot);
+        assert_eq!(field.snapshot(), snapshot);
+        assert_eq!(field.get_player_scores(), [0, 0]);
+    }
+
+    #[test]
+    fn snapshot_restore_serves() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let snapshot: FieldSnapshot = field.snapshot();
+        field.serve_new_ball(None);
+        let ball: Ball = field.ball;
+
+        // After rewinding, the same ball is served again.
+        field.restore(snapshot);
+        assert_eq!(field.snapshot(), snapshot);
+        field.serve_new_ball(None);
+        assert_eq!(field.ball, ball);
+    }
+
+    #[test]
+    fn snapshot_restore_practice_saves() {
+        let config = Config { practice_saves: 1, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let snapshot: FieldSnapshot = field.snapshot();
+        field.saves_remaining = [0, 0];
+
+        field.restore(snapshot);
+        assert_eq!(field.saves_remaining, [1, 1]);
+    }
+
+    #[test]
+    fn snapshot_restore_ai_decision_countdowns() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let snapshot: FieldSnapshot = field.snapshot();
+        field.ai_decision_countdowns = [0.5, 0.25];
+
+        field.restore(snapshot);
+        assert_eq!(field.ai_decision_countdowns, [0.0, 0.0]);
+    }
+
+    #[test]
+    fn snapshot_restore_flash() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let snapshot: FieldSnapshot = field.snapshot();
+        field.flash.start(color::WHITE);
+
+        field.restore(snapshot);
+        assert_eq!(field.flash, PointFlash::default());
+    }
+
+    #[test]
+    fn snapshot_restore_aim_angle() {
+        let mut field = Field::new([200, 100], &Config::default());
+        let snapshot: FieldSnapshot = field.snapshot();
+        field.set_aim_angle(0.5);
+
+        field.restore(snapshot);
+        assert_eq!(field.aim_angle, 0.0);
+    }
+
+    #[test]
+    fn resolve_paddle_overlap_horizontally() {
+        let mut players: [Player; 2] = [
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE),
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE)
+        ];
+        players[1].shift((4.0, 10.0));
+
+        Field::resolve_paddle_overlap(&mut players, 200);
+        assert_eq!(players[0].get_bounding_box(), [7.0, 0.0, 17.0, 60.0]);
+        assert_eq!(players[1].get_bounding_box(), [17.0, 10.0, 27.0, 70.0]);
+    }
+
+    #[test]
+    fn resolve_paddle_overlap_vertically() {
+        let mut players: [Player; 2] = [
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE),
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE)
+        ];
+        players[0].shift((0.0, 100.0));
+        players[1].shift((0.0, 50.0));
+
+        Field::resolve_paddle_overlap(&mut players, 200);
+        assert_eq!(players[0].get_bounding_box(), [10.0, 105.0, 20.0, 165.0]);
+        assert_eq!(players[1].get_bounding_box(), [10.0, 45.0, 20.0, 105.0]);
+    }
+
+    #[test]
+    fn resolve_paddle_overlap_within_field() {
+        let mut players: [Player; 2] = [
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE),
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE)
+        ];
+        players[0].shift((0.0, 56.0));
+
+        // The handles are not pushed beyond the top and bottom of the field.
+        Field::resolve_paddle_overlap(&mut players, 100);
+        assert_eq!(players[0].get_bounding_box(), [10.0, 40.0, 20.0, 100.0]);
+        assert_eq!(players[1].get_bounding_box(), [10.0, 0.0, 20.0, 60.0]);
+    }
+
+    #[test]
+    fn step_paddle_collision() {
+        // On a narrow field, the handles of both players overlap.
+        let mut field = Field::new([30, 100], &Config::default());
+        let _ = field.step(0.0);
+        assert_eq!(field.players[0].get_bounding_box(), field.players[1].get_bounding_box());
+
+        let config = Config { paddle_collision: true, ..Config::default() };
+        let mut field = Field::new([30, 100], &config);
+        let _ = field.step(0.0);
+        assert_eq!(field.players[0].get_bounding_box(), [5.0, 20.0, 15.0, 80.0]);
+        assert_eq!(field.players[1].get_bounding_box(), [15.0, 20.0, 25.0, 80.0]);
+    }
+
+    #[test]
+    fn resolve_paddle_overlap_apart() {
+        let mut players: [Player; 2] = [
+            Player::new(FieldSide::Left, 200, DEFAULT_HANDLE_SIZE),
+            Player::new(FieldSide::Right, 200, DEFAULT_HANDLE_SIZE)
+        ];
+        let expected: [Player; 2] = players;
+
+        Field::resolve_paddle_overlap(&mut players, 200);
+        assert_eq!(players, expected);
+    }
+
+    #[test]
+    fn is_active() {
+        let config = Config { first_serve_delay: 1.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        assert!(!field.is_active());
+
+        field.serve_countdown = 0.0;
+        assert!(field.is_active());
+
+        field.freeze();
+        assert!(!field.is_active());
+
+        field.unfreeze();
+        assert!(field.is_active());
+    }
+
+    #[test]
+    fn on_update_frozen() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.serve_countdown = 0.0;
+        field.players[0].set_movement(Movement::Down);
+        field.freeze();
+
+        let ball: [f64; 4] = field.ball.bounding_box();
+        let player: [f64; 4] = field.players[0].get_bounding_box();
+        assert_eq!(field.on_update(&UpdateArgs { dt: 0.1 }), BallStatus::WithinGame);
+        assert_eq!(field.ball.bounding_box(), ball);
+        assert_eq!(field.players[0].get_bounding_box(), player);
+        assert_eq!(field.elapsed_seconds(), 0.0);
+        assert_eq!(field.last_speed_change, 0.0);
+
+        // Once unfrozen, the field is updated again.
+        field.unfreeze();
+        assert_eq!(field.on_update(&UpdateArgs { dt: 0.1 }), BallStatus::WithinGame);
+        assert_ne!(field.ball.bounding_box(), ball);
+        assert_ne!(field.players[0].get_bounding_box(), player);
+    }
+
+    #[test]
+    fn on_update_paused() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.serve_countdown = 0.0;
+        field.last_speed_change = 1.0;
+
+        field.on_button_pressed(Button::Keyboard(Key::P));
+        field.on_button_released(Button::Keyboard(Key::P));
+        let ball: [f64; 4] = field.ball.bounding_box();
+        assert_eq!(field.on_update(&UpdateArgs { dt: 100.0 }), BallStatus::WithinGame);
+        assert_eq!(field.ball.bounding_box(), ball);
+        assert_eq!(field.last_speed_change, 1.0);
+
+        // Pressing the key again resumes the game.
+        field.on_button_pressed(Button::Keyboard(Key::P));
+        assert_eq!(field.on_update(&UpdateArgs { dt: 0.1 }), BallStatus::WithinGame);
+        assert_ne!(field.ball.bounding_box(), ball);
+        assert_eq!(field.last_speed_change, 1.1);
+    }
+
+    #[test]
+    fn on_button_pressed_paused() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.on_button_pressed(Button::Keyboard(Key::P));
+
+        // While paused, neither the handles move nor the sides switch.
+        field.on_button_pressed(Button::Keyboard(Key::S));
+        field.on_button_pressed(Button::Keyboard(Key::Tab));
+        assert_eq!(field.players[0].get_movement(), Movement::None);
+        assert_eq!(field.controlled_side, FieldSide::Left);
+
+        let handle: [f64; 4] = field.players[0].get_bounding_box();
+        field.on_touch([50.0, 10.0], Touch::Start);
+        assert_eq!(field.players[0].get_bounding_box(), handle);
+    }
+
+    #[test]
+    fn on_touch_won() {
+        let config = Config { target_score: Some(1), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.add_player_score(FieldSide::Left, 1);
+        field.update_scores(BallStatus::LeftOnRightSide);
+
+        let handle: [f64; 4] = field.players[0].get_bounding_box();
+        field.on_touch([50.0, 10.0], Touch::Start);
+        assert_eq!(field.players[0].get_bounding_box(), handle);
+    }
+
+    #[test]
+    fn on_button_pressed_pause_held() {
+        let mut field = Field::new([200, 100], &Config::default());
+
+        // Repeated presses while the key is held down only pause once.
+        for _ in 0..3 {
+            field.on_button_pressed(Button::Keyboard(Key::P));
+        }
+        assert!(field.frozen);
+
+        field.on_button_released(Button::Keyboard(Key::P));
+        field.on_button_pressed(Button::Keyboard(Key::P));
+        assert!(!field.frozen);
+    }
+
+    #[test]
+    fn on_update_serve_countdown() {
+        let config = Config { first_serve_delay: 1.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let ball: [f64; 4] = field.ball.bounding_box();
+        assert_eq!(field.on_update(&UpdateArgs { dt: 0.1 }), BallStatus::WithinGame);
+        assert_eq!(field.ball.bounding_box(), ball);
+        assert_eq!(field.rally_time, 0.0);
+    }
+
+    #[test]
+    fn step_elapsed_seconds() {
+        let config = Config { first_serve_delay: 0.1, ..Config::default() };
+        let mut field = Field::new([400, 100], &config);
+        assert_eq!(field.elapsed_seconds(), 0.0);
+
+        for dt in &[0.05, 0.1, 0.25] {
+            let _ = field.step(*dt);
+        }
+        assert!((field.elapsed_seconds() - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn step_players() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.players[0].set_movement(Movement::Up);
+        field.players[1].set_movement(Movement::Down);
+
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.players[0].get_bounding_box()[1], 5.0);
+        assert_eq!(field.players[1].get_bounding_box()[1], 35.0);
+        assert_eq!(field.last_speed_change, 0.1);
+    }
+
+    #[test]
+    fn step_serve_countdown() {
+        let config = Config { first_serve_delay: 0.2, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.players[0].set_movement(Movement::Down);
+        let ball: [f64; 4] = field.ball.bounding_box();
+
+        // Before the serve, the players can move, but the ball cannot.
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.serve_countdown, 0.1);
+        assert_eq!(field.players[0].get_bounding_box()[1], 35.0);
+        assert_eq!(field.ball.bounding_box(), ball);
+        assert_eq!(field.last_speed_change, 0.0);
+
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.serve_countdown, 0.0);
+        assert_eq!(field.ball.bounding_box(), ball);
+
+        // After the serve, the ball moves.
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_ne!(field.ball.bounding_box(), ball);
+    }
+
+    #[test]
+    fn step_serving_side() {
+        let config = Config { first_serve_delay: 0.1, serve_delay: 0.1, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.ball.set_speed((-100.0, 100.0));
+        assert_eq!(field.get_serving_side(), Some(FieldSide::Right));
+
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.get_serving_side(), None);
+
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        field.ball.set_speed((100.0, 100.0));
+        assert_eq!(field.get_serving_side(), Some(FieldSide::Left));
+    }
+
+    #[test]
+    fn step_serving_side_without_countdown() {
+        let field = Field::new([200, 100], &Config::default());
+        assert_eq!(field.get_serving_side(), None);
+    }
+
+    /// Create a large field with the ball already served, but resting, so that speed changes can be observed on the
+    /// ball's speed without the ball leaving the field.
+    fn ramp_field() -> Field {
+        let mut field = Field::new([2000, 2000], &Config::default());
+        field.serve_countdown = 0.0;
+        field.ball.set_speed((1.0, 0.0));
+        field
+    }
+
+    #[test]
+    fn step_speed_change_once() {
+        let mut field = ramp_field();
+        assert_eq!(field.step(SPEED_CHANGE_INTERVAL - 0.5), BallStatus::WithinGame);
+        assert_eq!(field.last_speed_change, SPEED_CHANGE_INTERVAL - 0.5);
+        assert_eq!(field.ball_speed(), (1.0, 0.0));
+
+        // Passing the interval changes the speed exactly once and starts the next interval.
+        assert_eq!(field.step(0.75), BallStatus::WithinGame);
+        assert_eq!(field.last_speed_change, 0.0);
+        assert_eq!(field.ball_speed(), (1.0 + SPEED_CHANGE, 0.0));
+
+        assert_eq!(field.step(0.25), BallStatus::WithinGame);
+        assert_eq!(field.last_speed_change, 0.25);
+        assert_eq!(field.ball_speed(), (1.0 + SPEED_CHANGE, 0.0));
+    }
+
+    #[test]
+    fn step_speed_change_small_steps() {
+        let mut field = ramp_field();
+        let dt: f64 = 0.25;
+        let steps: u32 = (2.0 * SPEED_CHANGE_INTERVAL / dt) as u32;
+        for _ in 0..steps {
+            assert_eq!(field.step(dt), BallStatus::WithinGame);
+        }
+
+        assert_eq!(field.last_speed_change, 0.0);
+        assert_eq!(field.ball_speed(), (1.0 + 2.0 * SPEED_CHANGE, 0.0));
+    }
+
+    #[test]
+    fn step_serve_countdown_pauses_speed_change() {
+        let config = Config { first_serve_delay: 3.0 * SPEED_CHANGE_INTERVAL, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let speed: (f64, f64) = field.ball.get_speed();
+
+        assert_eq!(field.step(2.0 * SPEED_CHANGE_INTERVAL), BallStatus::WithinGame);
+        assert_eq!(field.step(0.5 * SPEED_CHANGE_INTERVAL), BallStatus::WithinGame);
+        assert_eq!(field.last_speed_change, 0.0);
+        assert_eq!(field.ball.get_speed(), speed);
+    }
+
+    #[test]
+    fn step_serve_countdown_locked_paddles() {
+        let config = Config { first_serve_delay: 0.2, lock_paddles_on_serve: true, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.players[0].set_movement(Movement::Down);
+
+        // Before the serve, the players cannot move.
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.serve_countdown, 0.0);
+        assert_eq!(field.players[0].get_bounding_box()[1], 20.0);
+
+        // After the serve, the players move again.
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.players[0].get_bounding_box()[1], 35.0);
+    }
+
+    #[test]
+    fn step_serve_aimed() {
+        let config = Config { aim_serves: true, first_serve_delay: 0.1, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.ball.set_speed((30.0, 40.0));
+        field.set_aim_angle(0.0);
+
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.ball.get_speed(), (50.0, 0.0));
+    }
+
+    #[test]
+    fn step_handle_sweeps_into_ball() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.ball.set_speed((-1.0, -1.0));
+        assert_eq!(field.ball.bounding_box(), [95.0, 45.0, 105.0, 55.0]);
+
+        // Move the handle above the ball, so it sweeps over the ball within a single step.
+        field.players[0].shift((85.0, -80.0));
+        field.players[0].set_movement(Movement::Down);
+        assert_eq!(field.players[0].get_bounding_box(), [95.0, -60.0, 105.0, 0.0]);
+
+        assert_eq!(field.step(0.5), BallStatus::WithinGame);
+        assert_eq!(field.players[0].get_bounding_box(), [95.0, 15.0, 105.0, 75.0]);
+        assert_eq!(field.ball.get_speed(), (-1.0, 1.0));
+        assert_eq!(field.ball.bounding_box(), [94.5, 75.5, 104.5, 85.5]);
+        assert_eq!(field.ball.get_last_collision(), Some(0));
+    }
+
+    #[test]
+    fn step_deterministic() {
+        let mut field = Field::new([400, 100], &Config::default());
+        field.players[0].set_movement(Movement::Down);
+        let start: FieldSnapshot = field.snapshot();
+
+        let mut outcomes: Vec<BallStatus> = Vec::new();
+        for _ in 0..10 {
+            outcomes.push(field.step(0.05));
+        }
+        let end: FieldSnapshot = field.snapshot();
+
+        // The ball cannot have left the field yet, otherwise, the new ball would have been created randomly.
+        assert!(outcomes.iter().all(|outcome| *outcome == BallStatus::WithinGame));
+
+        // Simulating the same steps from the same state leads to the same outcomes and the same state.
+        field.restore(start);
+        for outcome in outcomes {
+            assert_eq!(field.step(0.05), outcome);
+        }
+        assert_eq!(field.snapshot(), end);
+    }
+
+    /// Replay the recorded match in `script` (in the format of `fixtures/demo_replay.txt`) on a new field.
+    ///
+    /// Returns the field at the end of the match and the scores the score hook was called with.
+    fn replay(script: &str) -> (Field, Vec<(FieldSide, isize)>) {
+        let changes: Rc<RefCell<Vec<(FieldSide, isize)>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut seed: Option<usize> = None;
+        let mut field: Option<Field> = None;
+
+        for line in script.lines() {
+            let words: Vec<&str> = line.split('#').next().unwrap_or("").split_whitespace().collect();
+            match words.as_slice() {
+                [] => {},
+                ["seed", value] => seed = Some(value.parse().unwrap()),
+                ["size", width, height] => {
+                    let config = Config { seed, ..Config::default() };
+                    let mut new_field = Field::new([width.parse().unwrap(), height.parse().unwrap()], &config);
+                    let recorded_changes = Rc::clone(&changes);
+                    new_field.set_score_hook(move |side, score| recorded_changes.borrow_mut().push((side, score)));
+                    field = Some(new_field);
+                },
+                [action, name] => {
+                    let field: &mut Field = field.as_mut().expect("The size must be set before any input.");
+                    let button = Button::Keyboard(controls::parse_key(name).expect("Unknown key."));
+                    match *action {
+                        "press" => field.on_button_pressed(button),
+                        "release" => field.on_button_released(button),
+                        _ => panic!("Unknown action \"{}\".", action),
+                    }
+                },
+                ["step", dt, count] => {
+                    let field: &mut Field = field.as_mut().expect("The size must be set before any steps.");
+                    let dt: f64 = dt.parse().unwrap();
+                    for _ in 0..count.parse::<usize>().unwrap() {
+                        let _ = field.step(dt);
+                    }
+                },
+                _ => panic!("Invalid line \"{}\".", line),
+            }
+        }
+
+        let changes: Vec<(FieldSide, isize)> = changes.borrow().clone();
+        (field.expect("The replay did not set up a field."), changes)
+    }
+
+    /// A recorded match must always lead to the same outcome. If this test fails after a deliberate change to the
+    /// simulation, update the expected results (after making sure the new outcome is plausible).
+    #[test]
+    fn replay_demo() {
+        let (field, changes) = replay(include_str!("fixtures/demo_replay.txt"));
+        assert_eq!(changes, vec![(FieldSide::Left, 1), (FieldSide::Right, 1), (FieldSide::Left, 2),
+                                 (FieldSide::Left, 3), (FieldSide::Right, 2), (FieldSide::Left, 4),
+                                 (FieldSide::Left, 5), (FieldSide::Right, 3)]);
+        assert_eq!(field.get_player_scores(), [5, 3]);
+        assert_eq!(field.ball.bounding_box(),
+                   [339.62773353088187, 95.0, 349.62773353088187, 105.0]);
+    }
+
+    #[test]
+    fn ball_center() {
+        let field = Field::new([200, 100], &Config::default());
+        assert_eq!(field.ball_center(), (100.0, 50.0));
+        assert_eq!(field.get_size(), [200, 100]);
+    }
+
+    #[test]
+    fn ball_speed() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.serve_countdown = 0.0;
+        for _ in 0..5 {
+            let _ = field.step(0.01);
+            let speed: (f64, f64) = field.ball.get_speed();
+            assert_eq!(field.ball_speed(), speed);
+            assert_eq!(field.ball_speed_magnitude(), speed.0.hypot(speed.1));
+        }
+    }
+
+    #[test]
+    fn summary() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.add_player_score(FieldSide::Left, 3);
+        field.add_player_score(FieldSide::Right, -2);
+        let speed: (f64, f64) = field.ball.get_speed();
+
+        let expected: String = format!("Field: 200x100\n\
+                                        Seed: {}\n\
+                                        Scores: 3 - -2\n\
+                                        Ball: position (95.00, 45.00), speed ({:.2}, {:.2}), {:.2} in total\n\
+                                        Players: left (10.00, 20.00), right (180.00, 20.00)\n\
+                                        Last speed change: 0.00s ago\n\
+                                        Elapsed time: 0.00s", field.get_seed(), speed.0, speed.1,
+                                       speed.0.hypot(speed.1));
+        assert_eq!(field.summary(), expected);
+    }
+
+    #[test]
+    fn update_scores_serve_delay() {
+        let config = Config { first_serve_delay: 3.0, serve_delay: 1.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        assert_eq!(field.serve_countdown, 3.0);
+
+        field.update_scores(BallStatus::WithinGame);
+        assert_eq!(field.serve_countdown, 3.0);
+
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.serve_countdown, 1.0);
+
+        field.serve_countdown = 0.0;
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.serve_countdown, 1.0);
+    }
+
+    #[test]
+    fn update_scores_base_point() {
+        let config = Config { targets: vec![(0.0, 20.0, 5)], ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [0, 1]);
+    }
+
+    #[test]
+    fn update_scores_target_bonus() {
+        let config = Config { targets: vec![(0.0, 20.0, 5), (40.0, 60.0, 3)], ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [4, 0]);
+    }
+
+    #[test]
+    fn update_scores_target_far_wall() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Easy)],
+                              targets: vec![(40.0, 60.0, 3)],
+                              ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        // Leaving through the controlled player's own wall only gives the opponent the usual point.
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [0, 1]);
+
+        // Leaving through the far wall gives the controlled player the bonus.
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [4, 1]);
+
+        // After switching sides, the targets are on the other wall.
+        field.switch_controlled_side();
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [5, 1]);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [5, 5]);
+    }
+
+    /// Let the ball in `field` collide with the player at index `player`.
+    fn touch_ball(field: &mut Field, player: usize) {
+        // An obstacle covering the entire field always collides with the ball, the others are out of reach.
+        let mut obstacles: [[f64; 4]; 2] = [[-20.0, -20.0, -10.0, -10.0]; 2];
+        obstacles[player] = [0.0, 0.0, f64::from(field.width), f64::from(field.height)];
+        let _ = field.ball.update(0.0, field.width, field.height, 0.0, &obstacles);
+        assert_eq!(field.ball.get_last_collision(), Some(player));
+    }
+
+    #[test]
+    fn update_scores_hook() {
+        let changes: Rc<RefCell<Vec<(FieldSide, isize)>>> = Rc::new(RefCell::new(Vec::new()));
+        let mut field = Field::new([200, 100], &Config::default());
+        let recorded_changes = Rc::clone(&changes);
+        field.set_score_hook(move |side, score| recorded_changes.borrow_mut().push((side, score)));
+
+        field.update_scores(BallStatus::WithinGame);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(*changes.borrow(), vec![(FieldSide::Right, 1), (FieldSide::Right, 2), (FieldSide::Left, 1)]);
+    }
+
+    #[test]
+    fn update_scores_hook_own_goal() {
+        let changes: Rc<RefCell<Vec<(FieldSide, isize)>>> = Rc::new(RefCell::new(Vec::new()));
+        let config = Config { scoring_rule: ScoringRule::OwnGoals, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let recorded_changes = Rc::clone(&changes);
+        field.set_score_hook(move |side, score| recorded_changes.borrow_mut().push((side, score)));
+
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(*changes.borrow(), vec![(FieldSide::Left, -1)]);
+    }
+
+    #[test]
+    fn update_scores_own_goal() {
+        let config = Config { scoring_rule: ScoringRule::OwnGoals, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [-1, 0]);
+        assert_eq!(field.ball.get_last_collision(), None);
+    }
+
+    #[test]
+    fn update_scores_own_goal_stats() {
+        let config = Config { scoring_rule: ScoringRule::OwnGoals, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_stats(), MatchStats { aces: [0, 0], longest_rally: 1, points: [0, 0] });
+
+        // A point won by the opponent is credited to them as usual.
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_stats(), MatchStats { aces: [0, 0], longest_rally: 1, points: [1, 0] });
+    }
+
+    #[test]
+    fn update_scores_own_goal_touched_by_opponent() {
+        let config = Config { scoring_rule: ScoringRule::OwnGoals, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [1, 0]);
+    }
+
+    #[test]
+    fn update_scores_standard_touched_by_self() {
+        let mut field = Field::new([200, 100], &Config::default());
+        touch_ball(&mut field, 1);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [1, 0]);
+    }
+
+    #[test]
+    fn update_scores_warm_up_miss() {
+        let config = Config { min_rally_to_score: 2, serve_delay: 1.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        touch_ball(&mut field, 1);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [0, 0]);
+        assert_eq!(field.ball.get_hits(), 0);
+        assert_eq!(field.serve_countdown, 1.0);
+    }
+
+    #[test]
+    fn update_scores_warm_up_over() {
+        let config = Config { min_rally_to_score: 2, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        touch_ball(&mut field, 1);
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [0, 1]);
+    }
+
+    /// Create a field in the given difficulty mode with the ball hitting the left handle within `0.08` seconds.
+    fn skill_field(difficulty_mode: DifficultyMode) -> Field {
+        let config = Config { difficulty_mode, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.serve_countdown = 0.0;
+        field.ball.set_speed((-500.0, 0.0));
+        field
+    }
+
+    #[test]
+    fn step_skill_mode_paddle_hit() {
+        let mut field = skill_field(DifficultyMode::Skill);
+        assert_eq!(field.step(0.16), BallStatus::WithinGame);
+        assert_eq!(field.ball.get_hits(), 1);
+        assert_eq!(field.ball_speed(), (500.0 + SPEED_CHANGE, 0.0));
+
+        // Without the skill mode, a hit does not change the speed.
+        let mut field = skill_field(DifficultyMode::Ramp);
+        assert_eq!(field.step(0.16), BallStatus::WithinGame);
+        assert_eq!(field.ball.get_hits(), 1);
+        assert_eq!(field.ball_speed(), (500.0, 0.0));
+    }
+
+    #[test]
+    fn step_skill_mode_no_time_ramp() {
+        let mut field = skill_field(DifficultyMode::Skill);
+        field.ball.set_speed((1.0, 0.0));
+        field.last_speed_change = SPEED_CHANGE_INTERVAL;
+        assert_eq!(field.step(0.0), BallStatus::WithinGame);
+        assert_eq!(field.ball_speed(), (1.0, 0.0));
+
+        // Without the skill mode, the ball speeds up over time.
+        let mut field = skill_field(DifficultyMode::Ramp);
+        field.ball.set_speed((1.0, 0.0));
+        field.last_speed_change = SPEED_CHANGE_INTERVAL;
+        assert_eq!(field.step(0.0), BallStatus::WithinGame);
+        assert_eq!(field.ball_speed(), (1.0 + SPEED_CHANGE, 0.0));
+    }
+
+    #[test]
+    fn step_skill_mode_no_speed_change_timer() {
+        let mut field = skill_field(DifficultyMode::Skill);
+        field.ball.set_speed((1.0, 0.0));
+        assert_eq!(field.step(0.5), BallStatus::WithinGame);
+        assert_eq!(field.last_speed_change, 0.0);
+
+        // Without the skill mode, the time since the last speed change is tracked.
+        let mut field = skill_field(DifficultyMode::Ramp);
+        field.ball.set_speed((1.0, 0.0));
+        assert_eq!(field.step(0.5), BallStatus::WithinGame);
+        assert_eq!(field.last_speed_change, 0.5);
+    }
+
+    #[test]
+    fn step_skill_mode_point_resets_speed() {
+        let mut field = skill_field(DifficultyMode::Skill);
+        assert_eq!(field.step(0.16), BallStatus::WithinGame);
+        assert!(field.ball_speed_magnitude() > 500.0);
+
+        // New balls start with at most the maximum base speed in each direction.
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [1, 0]);
+        assert!(field.ball_speed_magnitude() < 150.0 * 2.0_f64.sqrt());
+    }
+
+    #[test]
+    fn step_rally_cap_hits() {
+        let config = Config { rally_cap: Some(RallyCap::Hits(2)), serve_delay: 1.0, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        touch_ball(&mut field, 0);
+        assert_eq!(field.step(0.0), BallStatus::WithinGame);
+
+        touch_ball(&mut field, 1);
+        assert_eq!(field.step(0.0), BallStatus::Let);
+        assert_eq!(field.get_player_scores(), [0, 0]);
+        assert_eq!(field.ball.get_hits(), 0);
+        assert_eq!(field.serve_countdown, 1.0);
+    }
+
+    #[test]
+    fn step_rally_cap_seconds() {
+        let config = Config { first_serve_delay: 0.5, rally_cap: Some(RallyCap::Seconds(0.2)), serve_delay: 0.5,
+                              ..Config::default() };
+        let mut field = Field::new([400, 100], &config);
+
+        // The rally only starts once the ball is served.
+        assert_eq!(field.step(0.5), BallStatus::WithinGame);
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.step(0.1), BallStatus::Let);
+        assert_eq!(field.get_player_scores(), [0, 0]);
+        assert_eq!(field.rally_time, 0.0);
+        assert_eq!(field.serve_countdown, 0.5);
+    }
+
+    #[test]
+    fn update_scores_serve_cone() {
+        let config = Config { serve_cone: Some(30.0), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        for _ in 0..20 {
+            field.update_scores(BallStatus::LeftOnLeftSide);
+            assert!(field.ball.get_speed().0 < 0.0);
+
+            field.update_scores(BallStatus::LeftOnRightSide);
+            assert!(field.ball.get_speed().0 > 0.0);
+        }
+    }
+
+    #[test]
+    fn update_scores_serve_speed() {
+        let config = Config { serve_speed: Some(180.0), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        assert!((field.ball_speed_magnitude() - 180.0).abs() < 1e-9);
+        for _ in 0..20 {
+            field.update_scores(BallStatus::LeftOnLeftSide);
+            assert!((field.ball_speed_magnitude() - 180.0).abs() < 1e-9);
+        }
+
+        // Directed serves are normalized as well.
+        let config = Config { serve_cone: Some(30.0), serve_speed: Some(180.0), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        for _ in 0..20 {
+            field.update_scores(BallStatus::LeftOnRightSide);
+            assert!(field.ball_speed().0 > 0.0);
+            assert!((field.ball_speed_magnitude() - 180.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn step_practice_saves() {
+        let config = Config { practice_saves: 1, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.serve_countdown = 0.0;
+        field.players[0].shift((0.0, -1000.0));
+        assert_eq!(field.saves_remaining, [1, 1]);
+
+        // The practice wall returns the first miss.
+        field.ball.set_speed((-2000.0, 0.0));
+        assert_eq!(field.step(0.1), BallStatus::WithinGame);
+        assert_eq!(field.ball_speed(), (2000.0, 0.0));
+        assert_eq!(field.saves_remaining, [0, 1]);
+        assert_eq!(field.get_player_scores(), [0, 0]);
+
+        // Once the saves are used up, a miss scores normally.
+        field.ball.set_speed((-2000.0, 0.0));
+        assert_eq!(field.step(0.1), BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [0, 1]);
+    }
+
+    #[test]
+    fn practice_saves_keyboard_only() {
+        let config = Config { input_sources: [InputSource::Keyboard, InputSource::Ai(AiLevel::Hard)],
+                              practice_saves: 3, ..Config::default() };
+        let field = Field::new([200, 100], &config);
+        assert_eq!(field.saves_remaining, [3, 0]);
+    }
+
+    #[test]
+    fn update_scores_point_flash() {
+        let blue: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+        let config = Config { point_flash_colors: Some([blue, color::RED]), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        assert!(!field.flash.is_visible());
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert!(field.flash.is_visible());
+        assert_eq!(field.flash.color()[..3], blue[..3]);
+
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.flash.color()[..3], color::RED[..3]);
+    }
+
+    #[test]
+    fn update_scores_without_point_flash() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert!(!field.flash.is_visible());
+    }
+
+    #[test]
+    fn update_scores_let() {
+        let mut field = Field::new([200, 100], &Config::default());
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::Let);
+        assert_eq!(field.get_player_scores(), [0, 0]);
+        assert_eq!(field.ball.get_last_collision(), None);
+    }
+
+    #[test]
+    fn update_scores_recenter_paddles() {
+        let config = Config { recenter_paddles_on_point: true, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        let centered: [[f64; 4]; 2] = [field.players[0].get_bounding_box(), field.players[1].get_bounding_box()];
+        field.players[0].shift((0.0, -20.0));
+        field.players[1].shift((0.0, 15.0));
+
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!([field.players[0].get_bounding_box(), field.players[1].get_bounding_box()], centered);
+    }
+
+    #[test]
+    fn update_scores_paddles_stay() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.players[0].shift((0.0, -20.0));
+        field.players[1].shift((0.0, 15.0));
+        let moved: [[f64; 4]; 2] = [field.players[0].get_bounding_box(), field.players[1].get_bounding_box()];
+
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!([field.players[0].get_bounding_box(), field.players[1].get_bounding_box()], moved);
+    }
+
+    #[test]
+    fn update_scores_stats_ace() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_stats(), MatchStats { aces: [0, 1], longest_rally: 0, points: [0, 1] });
+    }
+
+    #[test]
+    fn update_scores_stats_touched() {
+        let mut field = Field::new([200, 100], &Config::default());
+        touch_ball(&mut field, 1);
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_stats(), MatchStats { aces: [0, 0], longest_rally: 2, points: [1, 0] });
+
+        touch_ball(&mut field, 0);
+        field.update_scores(BallStatus::Let);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_stats(), MatchStats { aces: [1, 0], longest_rally: 2, points: [2, 0] });
+    }
+
+    /// Get the heights of the players' handles in `field`.
+    fn handle_heights(field: &Field) -> [f64; 2] {
+        let left: [f64; 4] = field.players[0].get_bounding_box();
+        let right: [f64; 4] = field.players[1].get_bounding_box();
+        [left[3] - left[1], right[3] - right[1]]
+    }
+
+    #[test]
+    fn is_active_won() {
+        let config = Config { target_score: Some(1), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.serve_countdown = 0.0;
+        assert!(field.is_active());
+
+        field.add_player_score(FieldSide::Left, 1);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        field.serve_countdown = 0.0;
+        assert!(!field.is_active());
+    }
+
+    #[test]
+    fn update_scores_target_score() {
+        let config = Config { target_score: Some(3), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.winner(), None);
+        assert_eq!(field.state, GameState::Playing);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [3, 0]);
+        assert_eq!(field.winner(), Some(FieldSide::Left));
+        assert_eq!(field.state, GameState::Won(FieldSide::Left));
+    }
+
+    #[test]
+    fn update_scores_target_score_lead() {
+        let config = Config { target_score: Some(3), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.add_player_score(FieldSide::Left, 2);
+        field.add_player_score(FieldSide::Right, 2);
+
+        // Reaching the target score is not enough without a lead of two points.
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.get_player_scores(), [2, 3]);
+        assert_eq!(field.winner(), None);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [4, 3]);
+        assert_eq!(field.winner(), None);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(field.get_player_scores(), [5, 3]);
+        assert_eq!(field.winner(), Some(FieldSide::Left));
+    }
+
+    #[test]
+    fn update_scores_without_target_score() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.add_player_score(FieldSide::Right, 100);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.winner(), None);
+    }
+
+    #[test]
+    fn step_won() {
+        let config = Config { target_score: Some(1), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.add_player_score(FieldSide::Right, 1);
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.winner(), Some(FieldSide::Right));
+
+        // Once the match is won, neither the ball nor the players move anymore.
+        field.serve_countdown = 0.0;
+        field.ball.set_speed((100.0, 100.0));
+        field.players[0].set_movement(Movement::Down);
+        let ball: [f64; 4] = field.ball.bounding_box();
+        let player: [f64; 4] = field.players[0].get_bounding_box();
+        assert_eq!(field.on_update(&UpdateArgs { dt: 0.1 }), BallStatus::WithinGame);
+        assert_eq!(field.ball.bounding_box(), ball);
+        assert_eq!(field.players[0].get_bounding_box(), player);
+        assert_eq!(field.elapsed_seconds(), 0.0);
+    }
+
+    #[test]
+    fn restore_won() {
+        let config = Config { target_score: Some(1), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.add_player_score(FieldSide::Right, 1);
+        let snapshot: FieldSnapshot = field.snapshot();
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(field.winner(), Some(FieldSide::Right));
+
+        // Rewinding to before the winning point continues the match.
+        field.restore(snapshot);
+        assert_eq!(field.winner(), None);
+    }
+
+    #[test]
+    fn update_scores_handicap() {
+        let config = Config { handicap: true, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(handle_heights(&field), [55.0, 65.0]);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(handle_heights(&field), [50.0, 70.0]);
+
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(handle_heights(&field), [55.0, 65.0]);
+    }
+
+    #[test]
+    fn update_scores_handicap_handle_sizes() {
+        let config = Config { handicap: true, handle_sizes: [(10.0, 40.0), (10.0, 80.0)], ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(handle_heights(&field), [35.0, 85.0]);
+    }
+
+    #[test]
+    fn update_scores_handicap_bounds() {
+        let config = Config { handicap: true, ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+        field.add_player_score(FieldSide::Right, ::std::isize::MAX);
+
+        // The growing handle is limited to the field's height.
+        field.update_scores(BallStatus::LeftOnLeftSide);
+        assert_eq!(handle_heights(&field), [100.0, 20.0]);
+        let left: [f64; 4] = field.players[0].get_bounding_box();
+        assert!(left[1] >= 0.0 && left[3] <= 100.0);
+    }
+
+    #[test]
+    fn update_scores_without_handicap() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.update_scores(BallStatus::LeftOnRightSide);
+        assert_eq!(handle_heights(&field), [DEFAULT_HANDLE_SIZE.1, DEFAULT_HANDLE_SIZE.1]);
+    }
+
+    #[test]
+    fn top_inset() {
+        let field = Field::new([200, 100], &Config::default());
+        assert_eq!(field.top_inset(), 0.0);
+
+        let config = Config { top_line_collision: true, ..Config::default() };
+        let field = Field::new([200, 100], &config);
+        assert_eq!(field.top_inset(), 2.0);
+    }
+
+    #[test]
+    fn update_speed_burst() {
+        let burst = SpeedBurst { duration: 1.0, interval: 2.0, multiplier: 1.5 };
+        let config = Config { speed_burst: Some(burst), ..Config::default() };
+        let mut field = Field::new([200, 100], &config);
+
+        field.update_speed_burst(1.5);
+        assert_eq!(field.ball.get_speed_multiplier(), 1.0);
+
+        // The burst engages after the interval.
+        field.update_speed_burst(0.5);
+        assert_eq!(field.ball.get_speed_multiplier(), 1.5);
+
+        field.update_speed_burst(0.5);
+        assert_eq!(field.ball.get_speed_multiplier(), 1.5);
+
+        // The burst reverts after its duration.
+        field.update_speed_burst(0.5);
+        assert_eq!(field.ball.get_speed_multiplier(), 1.0);
+        assert_eq!(field.speed_burst_timer, 0.0);
+    }
+
+    #[test]
+    fn update_speed_burst_disabled() {
+        let mut field = Field::new([200, 100], &Config::default());
+        field.update_speed_burst(100.0);
+        assert_eq!(field.ball.get_speed_multiplier(), 1.0);
+        assert_eq!(field.speed_burst_timer, 0.0);
     }
 
     #[test]
     fn on_resize() {
-        let mut field = Field::new([200, 100]);
+        let mut field = Field::new([200, 100], &Config::default());
         field.on_resize(100, 200);
         assert_eq!(field.width, 100);
         assert_eq!(field.height, 200);
@@ -14,6 +14,7 @@ mod scoreboard;
 pub use self::ball::Ball;
 pub use self::ball::BallStatus;
 pub use self::field::Field;
+pub use self::field::MatchStats;
 pub use self::player::FieldSide;
 pub use self::player::Movement;
 pub use self::player::Player;
@@ -16,6 +16,15 @@ use quickcheck::Arbitrary;
 use quickcheck::Gen;
 
 use color;
+use config::MAXIMUM_HANDLE_HEIGHT;
+use config::MINIMUM_HANDLE_HEIGHT;
+use pixels;
+
+/// The number of previous positions of the player's handle drawn as its after-image.
+const AFTER_IMAGE_LENGTH: usize = 4;
+
+/// The minimum speed of the player's handle at which its after-image is drawn.
+const AFTER_IMAGE_SPEED: f64 = 200.0;
 
 /// The margin between the player's handle and the respective edge of the field.
 const PLAYER_MARGIN: f64 = 10.0;
@@ -36,6 +45,17 @@ pub enum Movement {
     Up,
 }
 
+impl Movement {
+    /// Get the direction of the movement on the y-axis: `-1` when moving up, `1` when moving down, and `0` otherwise.
+    pub fn direction(self) -> f64 {
+        match self {
+            Movement::Down => 1.0,
+            Movement::None => 0.0,
+            Movement::Up => -1.0,
+        }
+    }
+}
+
 #[cfg(test)]
 impl Arbitrary for Movement {
     /// Implement the `Arbitrary` trait so this enum can be used in `quickcheck` tests.
@@ -70,11 +90,21 @@ impl FieldSide {
 }
 
 /// The player.
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Player {
+    /// Draw an after-image of the player's handle at its previous positions when it moves fast.
+    after_image: bool,
+
     /// The player's position on the field.
     field_side: FieldSide,
 
+    /// The previous positions of the player's handle, the most recent one first. Only the first `history_length`
+    /// entries are valid.
+    history: [(f64, f64); AFTER_IMAGE_LENGTH],
+
+    /// The number of valid entries in `history`.
+    history_length: usize,
+
     /// The current direction of movement.
     movement: Movement,
 
@@ -92,18 +122,20 @@ pub struct Player {
 }
 
 impl Player {
-    /// Create a new player at position `(x, y)`.
-    pub fn new(side: FieldSide, field_width: u32) -> Player {
-        let size: (f64, f64) = (10.0, 60.0);
+    /// Create a new player on the given `side` whose handle has the given `size`: `(width, height)`.
+    pub fn new(side: FieldSide, field_width: u32, size: (f64, f64)) -> Player {
         let y: f64 = 0.0;
         let x: f64 = side.get_x_position(size.0, field_width);
 
         Player {
+            after_image: false,
             field_side: side,
+            history: [(x, y); AFTER_IMAGE_LENGTH],
+            history_length: 0,
             movement: Movement::None,
             position: (x, y),
             score: 0,
-            size: (10.0, 60.0),
+            size,
             speed: SPEED,
         }
     }
@@ -113,10 +145,22 @@ impl Player {
         self.speed += amount;
     }
 
-    /// Draw the player.
-    pub fn draw(&self, context: &Context, graphics: &mut G2d) {
+    /// Draw the player. If a `pixel_scale` (device pixels per point) is given, the handle is snapped to whole pixels.
+    pub fn draw(&self, context: &Context, graphics: &mut G2d, pixel_scale: Option<f64>) {
+        // Draw the after-image as increasingly faint copies of the handle at its previous positions.
+        if self.is_showing_after_image() {
+            for (index, position) in self.history[..self.history_length].iter().enumerate() {
+                let alpha: f32 = 0.5 * (1.0 - index as f32 / AFTER_IMAGE_LENGTH as f32);
+                let after_image = Rectangle::new(color::with_alpha(color::WHITE, alpha));
+                let position: (f64, f64) = pixels::snap_point(*position, pixel_scale);
+                let transformation = context.transform.trans(position.0, position.1);
+                after_image.draw([0.0, 0.0, self.size.0, self.size.1], &context.draw_state, transformation, graphics);
+            }
+        }
+
         let handle = Rectangle::new(color::WHITE);
-        let transformation = context.transform.trans(self.position.0, self.position.1);
+        let position: (f64, f64) = pixels::snap_point(self.position, pixel_scale);
+        let transformation = context.transform.trans(position.0, position.1);
         handle.draw([0.0, 0.0, self.size.0, self.size.1], &context.draw_state, transformation, graphics);
     }
 
@@ -131,33 +175,87 @@ impl Player {
         ]
     }
 
+    /// Get the current direction of movement.
+    #[cfg(test)]
+    pub fn get_movement(&self) -> Movement {
+        self.movement
+    }
+
+    /// Determine if the after-image of the player's handle is currently drawn: it must be enabled and the handle
+    /// must move at least at `AFTER_IMAGE_SPEED`.
+    fn is_showing_after_image(&self) -> bool {
+        self.after_image && self.movement != Movement::None && self.speed >= AFTER_IMAGE_SPEED
+    }
+
     /// Get the player's current score.
     pub fn get_score(&self) -> isize {
         self.score
     }
 
+    /// Move the player's handle to the vertical center of a field with the given height. If the field is smaller than
+    /// the handle, the handle is moved to the top.
+    pub fn move_to_center(&mut self, field_height: u32) {
+        self.position.1 = ((f64::from(field_height) - self.size.1) / 2.0).max(0.0);
+    }
+
+    /// Set if an after-image is drawn behind the player's handle when it moves fast.
+    pub fn set_after_image(&mut self, after_image: bool) {
+        self.after_image = after_image;
+    }
+
+    /// Set the height of the player's handle, keeping its vertical center as far as the handle stays within a field
+    /// of the given height. The height is limited to the range from `MINIMUM_HANDLE_HEIGHT` to `MAXIMUM_HANDLE_HEIGHT`,
+    /// and to the field's height.
+    pub fn set_height(&mut self, height: f64, field_height: u32) {
+        let height: f64 = height.clamp(MINIMUM_HANDLE_HEIGHT, MAXIMUM_HANDLE_HEIGHT).min(f64::from(field_height));
+        let y: f64 = self.position.1 + (self.size.1 - height) / 2.0;
+        self.size.1 = height;
+        self.set_position_y(y, field_height);
+    }
+
+    /// Move the top of the player's handle to the vertical position `y`, keeping the handle within a field of the
+    /// given height. If the field is smaller than the handle, the handle is moved to the top.
+    pub fn set_position_y(&mut self, y: f64, field_height: u32) {
+        self.position.1 = y.min(f64::from(field_height) - self.size.1).max(0.0);
+    }
+
     /// Move the player.
     pub fn set_movement(&mut self, movement: Movement) {
         self.movement = movement;
     }
 
+    /// Move the player's handle by the given `offset`: `(x, y)`.
+    pub fn shift(&mut self, offset: (f64, f64)) {
+        self.position.0 += offset.0;
+        self.position.1 += offset.1;
+    }
+
     /// Update the player's position.
     pub fn update(&mut self, dt: f64, height: u32) {
-        match self.movement {
-            Movement::Down => {
-                self.position.1 += self.speed * dt;
-                if self.position.1 + self.size.1 > f64::from(height) {
-                    self.position.1 = f64::from(height) - self.size.1;
-                }
-            },
-            Movement::Up => {
-                self.position.1 -= self.speed * dt;
-                if self.position.1 < 0.0 {
-                    self.position.1 = 0.0;
-                }
-            },
-            _ => {},
+        if self.after_image {
+            self.record_position();
         }
+
+        if self.movement == Movement::None {
+            return;
+        }
+
+        // Keep the handle within the field. If the field is smaller than the handle, keep the handle at the top.
+        let position_y: f64 = self.position.1 + self.movement.direction() * self.speed * dt;
+        self.position.1 = position_y.min(f64::from(height) - self.size.1).max(0.0);
+    }
+
+    /// Add the current position of the player's handle to the front of its history, dropping the oldest entry if
+    /// the history is full.
+    fn record_position(&mut self) {
+        self.history.copy_within(..AFTER_IMAGE_LENGTH - 1, 1);
+        self.history[0] = self.position;
+        self.history_length = (self.history_length + 1).min(AFTER_IMAGE_LENGTH);
+    }
+
+    /// Set the player's score to `score`, e.g. to start a match with a head start.
+    pub fn set_score(&mut self, score: isize) {
+        self.score = score;
     }
 
     /// Update the player's score with `additional_points`.
@@ -193,6 +291,7 @@ mod tests {
     #![allow(trivial_casts)]
 
     use quickcheck::TestResult;
+    use config::DEFAULT_HANDLE_SIZE;
     use super::*;
 
     #[test]
@@ -211,7 +310,9 @@ mod tests {
 
     #[test]
     fn new() {
-        let player = Player::new(FieldSide::Left, 42);
+        let player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        assert!(!player.after_image);
+        assert_eq!(player.history_length, 0);
         assert_eq!(player.movement, Movement::None);
         assert_eq!(player.position, (PLAYER_MARGIN, 0.0));
         assert_eq!(player.score, 0);
@@ -219,9 +320,17 @@ mod tests {
         assert_eq!(player.speed, 150.0);
     }
 
+    #[test]
+    fn new_custom_size() {
+        let player = Player::new(FieldSide::Right, 100, (20.0, 80.0));
+        assert_eq!(player.position, (80.0 - PLAYER_MARGIN, 0.0));
+        assert_eq!(player.size, (20.0, 80.0));
+        assert_eq!(player.history, [player.position; AFTER_IMAGE_LENGTH]);
+    }
+
     #[test]
     fn change_speed() {
-        let mut player = Player::new(FieldSide::Left, 42);
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
         player.speed = 42.0;
         player.change_speed(10.0);
         assert_eq!(player.speed, 52.0);
@@ -229,7 +338,7 @@ mod tests {
 
     #[test]
     fn get_bounding_box() {
-        let player = Player::new(FieldSide::Left, 42);
+        let player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
         let bounding_box = player.get_bounding_box();
         assert_eq!(bounding_box[0], PLAYER_MARGIN);
         assert_eq!(bounding_box[1], 0.0);
@@ -237,23 +346,143 @@ mod tests {
         assert_eq!(bounding_box[3], 60.0);
     }
 
+    #[test]
+    fn get_bounding_box_custom_size() {
+        let player = Player::new(FieldSide::Right, 100, (20.0, 80.0));
+        assert_eq!(player.get_bounding_box(), [80.0 - PLAYER_MARGIN, 0.0, 100.0 - PLAYER_MARGIN, 80.0]);
+    }
+
     #[test]
     fn get_score() {
-        let mut player = Player::new(FieldSide::Left, 42);
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
         let score: isize = 42;
         player.score = score;
         assert_eq!(player.get_score(), score);
     }
 
+    #[test]
+    fn set_score() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.set_score(3);
+        assert_eq!(player.get_score(), 3);
+    }
+
+    #[test]
+    fn move_to_center() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.move_to_center(100);
+        let bounding_box = player.get_bounding_box();
+        assert_eq!((bounding_box[1] + bounding_box[3]) / 2.0, 50.0);
+        assert_eq!(player.position.0, PLAYER_MARGIN);
+    }
+
+    #[test]
+    fn move_to_center_small_field() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.move_to_center(50);
+        assert_eq!(player.position.1, 0.0);
+    }
+
+    #[test]
+    fn set_height() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.position.1 = 100.0;
+        player.set_height(40.0, 200);
+        assert_eq!(player.size, (10.0, 40.0));
+        assert_eq!(player.position.1, 110.0);
+    }
+
+    #[test]
+    fn set_height_within_field() {
+        // A handle growing at the bottom of the field is moved up to stay within the field.
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.position.1 = 140.0;
+        player.set_height(100.0, 200);
+        assert_eq!(player.size, (10.0, 100.0));
+        assert_eq!(player.position.1, 100.0);
+
+        // A handle growing at the top of the field is moved down to stay within the field.
+        player.position.1 = 10.0;
+        player.set_height(120.0, 200);
+        assert_eq!(player.size, (10.0, 120.0));
+        assert_eq!(player.position.1, 0.0);
+
+        // A handle does not grow taller than the field.
+        player.set_height(120.0, 100);
+        assert_eq!(player.size, (10.0, 100.0));
+        assert_eq!(player.position.1, 0.0);
+    }
+
+    quickcheck! {
+        fn set_height_bounds(height: f64) -> bool {
+            let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+            player.set_height(height, 200);
+
+            MINIMUM_HANDLE_HEIGHT <= player.size.1 && player.size.1 <= MAXIMUM_HANDLE_HEIGHT
+        }
+    }
+
+    #[test]
+    fn set_position_y() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.set_position_y(25.0, 100);
+        assert_eq!(player.position, (PLAYER_MARGIN, 25.0));
+
+        player.set_position_y(-10.0, 100);
+        assert_eq!(player.position.1, 0.0);
+
+        player.set_position_y(90.0, 100);
+        assert_eq!(player.position.1, 40.0);
+
+        player.set_position_y(10.0, 50);
+        assert_eq!(player.position.1, 0.0);
+    }
+
+    #[test]
+    fn shift() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.shift((5.0, -2.5));
+        assert_eq!(player.position, (15.0, -2.5));
+    }
+
     quickcheck! {
         fn set_movement(movement: Movement) -> bool {
-        let mut player = Player::new(FieldSide::Left, 42);
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
         player.set_movement(movement);
 
         player.movement == movement
         }
     }
 
+    #[test]
+    fn direction() {
+        assert_eq!(Movement::Down.direction(), 1.0);
+        assert_eq!(Movement::None.direction(), 0.0);
+        assert_eq!(Movement::Up.direction(), -1.0);
+    }
+
+    #[test]
+    fn update_stop_at_edges() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.position.1 = 20.0;
+
+        player.set_movement(Movement::Up);
+        player.update(0.1, 100);
+        assert_eq!(player.position.1, 5.0);
+        player.update(0.1, 100);
+        assert_eq!(player.position.1, 0.0);
+
+        player.set_movement(Movement::Down);
+        player.update(0.1, 100);
+        assert_eq!(player.position.1, 15.0);
+        player.update(1.0, 100);
+        assert_eq!(player.position.1, 100.0 - player.size.1);
+
+        player.set_movement(Movement::None);
+        player.update(1.0, 100);
+        assert_eq!(player.position.1, 100.0 - player.size.1);
+    }
+
     quickcheck! {
         fn update(position: (f64, f64), dt: f64, height: u32, movement: Movement) -> TestResult {
             // Time only advances, the position cannot be negative.
@@ -261,7 +490,7 @@ mod tests {
                 return TestResult::discard();
             }
 
-            let mut player = Player::new(FieldSide::Left, (position.1 * 2.0) as u32);
+            let mut player = Player::new(FieldSide::Left, (position.1 * 2.0) as u32, DEFAULT_HANDLE_SIZE);
             player.position = position;
             player.set_movement(movement);
             player.update(dt, height);
@@ -294,9 +523,75 @@ mod tests {
         }
     }
 
+    #[test]
+    fn is_showing_after_image() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.set_after_image(true);
+        player.set_movement(Movement::Down);
+        player.speed = AFTER_IMAGE_SPEED;
+        assert!(player.is_showing_after_image());
+
+        player.speed = SPEED;
+        assert!(!player.is_showing_after_image());
+
+        player.speed = AFTER_IMAGE_SPEED;
+        player.set_movement(Movement::None);
+        assert!(!player.is_showing_after_image());
+
+        player.set_movement(Movement::Down);
+        player.set_after_image(false);
+        assert!(!player.is_showing_after_image());
+    }
+
+    #[test]
+    fn update_history() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.set_after_image(true);
+        player.set_movement(Movement::Down);
+        player.speed = 100.0;
+
+        for step in 0..AFTER_IMAGE_LENGTH + 2 {
+            player.update(0.1, 1000);
+            assert_eq!(player.history_length, (step + 1).min(AFTER_IMAGE_LENGTH));
+        }
+
+        // The most recent position comes first: the one before the last update.
+        let expected: Vec<(f64, f64)> = (0..AFTER_IMAGE_LENGTH)
+            .map(|index| (PLAYER_MARGIN, 10.0 * (AFTER_IMAGE_LENGTH + 1 - index) as f64))
+            .collect();
+        assert_eq!(player.history.to_vec(), expected);
+        assert_eq!(player.position, (PLAYER_MARGIN, 10.0 * (AFTER_IMAGE_LENGTH + 2) as f64));
+    }
+
+    #[test]
+    fn update_history_disabled() {
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+        player.set_movement(Movement::Down);
+        player.update(0.1, 1000);
+        assert_eq!(player.history_length, 0);
+    }
+
+    quickcheck! {
+        fn update_within_field(extra_height: u16, offset: u32, speed: u16, milliseconds: u16, movement: Movement)
+            -> bool {
+            // Map the arbitrary values onto valid states: the field is at least as high as the handle, which starts
+            // anywhere within the field.
+            let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
+            let height: u32 = player.size.1 as u32 + u32::from(extra_height);
+            player.position.1 = f64::from(offset % (u32::from(extra_height) + 1));
+            player.speed = f64::from(speed);
+            player.set_movement(movement);
+
+            player.update(f64::from(milliseconds) / 1000.0, height);
+
+            let bounding_box: [f64; 4] = player.get_bounding_box();
+            bounding_box[1] >= 0.0 && bounding_box[3] <= f64::from(height)
+        }
+    }
+
     quickcheck! {
         fn update_score(old_score: isize, additional_points: isize, speed: f64) -> bool {
-            let mut player = Player::new(FieldSide::Left, 42);
+            let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
             player.speed = speed;
             player.score = old_score;
             player.update_score(additional_points);
@@ -321,7 +616,7 @@ mod tests {
 
     #[test]
     fn update_score_upper_overflow() {
-        let mut player = Player::new(FieldSide::Left, 42);
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
         player.score = ::std::isize::MAX;
         player.update_score(1);
         assert_eq!(player.score, ::std::isize::MAX);
@@ -329,7 +624,7 @@ mod tests {
 
     #[test]
     fn update_score_lower_overflow() {
-        let mut player = Player::new(FieldSide::Left, 42);
+        let mut player = Player::new(FieldSide::Left, 42, DEFAULT_HANDLE_SIZE);
         player.score = ::std::isize::MIN;
         player.update_score(-1);
         assert_eq!(player.score, ::std::isize::MIN);
@@ -337,8 +632,16 @@ mod tests {
 
     #[test]
     fn update_position() {
-        let mut player = Player::new(FieldSide::Right, 42);
+        let mut player = Player::new(FieldSide::Right, 42, DEFAULT_HANDLE_SIZE);
         player.update_position(60);
         assert_eq!(player.position, (50.0 - PLAYER_MARGIN, 0.0));
     }
+
+    #[test]
+    fn update_position_custom_size() {
+        let mut player = Player::new(FieldSide::Right, 100, (20.0, 80.0));
+        player.update_position(60);
+        assert_eq!(player.position, (40.0 - PLAYER_MARGIN, 0.0));
+        assert_eq!(player.get_bounding_box(), [40.0 - PLAYER_MARGIN, 0.0, 60.0 - PLAYER_MARGIN, 80.0]);
+    }
 }
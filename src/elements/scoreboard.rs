@@ -14,6 +14,43 @@ use piston_window::character::CharacterCache;
 use piston_window::text::Text;
 
 use color;
+use config::Config;
+use elements::FieldSide;
+use pixels;
+
+/// The horizontal margin between the scores and the edges of the scoreboard.
+const MARGIN: f64 = 10.0;
+
+/// The marker shown next to the score of the serving player.
+const SERVING_MARKER: &str = "*";
+
+/// Measure the dimensions of text.
+pub trait FontMetrics {
+    /// Get the width of `text` when drawn with the given font `size`.
+    fn text_width(&mut self, size: u32, text: &str) -> f64;
+}
+
+impl FontMetrics for Glyphs {
+    fn text_width(&mut self, size: u32, text: &str) -> f64 {
+        self.width(size, text).unwrap_or(0.0)
+    }
+}
+
+/// A text positioned on the scoreboard.
+#[derive(Clone, Debug, PartialEq)]
+struct PositionedText {
+    /// The text itself.
+    text: String,
+
+    /// The x-position of the text's left edge.
+    x: f64,
+
+    /// The y-position of the text's baseline.
+    y: f64,
+
+    /// The width of the text.
+    width: f64,
+}
 
 /// Alignment of text.
 enum TextAlignment {
@@ -38,81 +75,185 @@ impl TextAlignment {
     }
 }
 
+/// Abbreviate the `score` with one decimal place (rounded towards zero) and a suffix, e.g. `1234567` to `1.2M`. Scores
+/// with less than four digits are not abbreviated.
+fn abbreviate_score(score: isize) -> String {
+    let suffixes: [&str; 6] = ["K", "M", "B", "T", "Qa", "Qi"];
+    if -1000 < score && score < 1000 {
+        return score.to_string();
+    }
+
+    // Find the largest unit (a power of 1000) the score can be expressed in.
+    let mut unit: isize = 1000;
+    let mut suffix: usize = 0;
+    while score / unit / 1000 != 0 && suffix + 1 < suffixes.len() {
+        unit *= 1000;
+        suffix += 1;
+    }
+
+    let tenths: isize = score / (unit / 10);
+    let sign: &str = if tenths < 0 { "-" } else { "" };
+    format!("{}{}.{}{}", sign, (tenths / 10).abs(), (tenths % 10).abs(), suffixes[suffix])
+}
+
 /// The scoreboard displays information on the game, such as the current score and the name.
 #[derive(Clone, Debug, Default)]
 pub struct Scoreboard {
     /// The name of the game.
     title: String,
 
+    /// The positioned texts of the last layout, or `None` if the layout is outdated and must be recomputed.
+    cached_layout: Option<[PositionedText; 3]>,
+
     /// The height of the scoreboard.
     height: u32,
 
+    /// Draw the text larger and bold.
+    large_text: bool,
+
+    /// The maximum number of digits of a score shown before it is abbreviated.
+    max_score_digits: usize,
+
     /// The width of the scoreboard.
     width: u32,
 
     /// The players' scores.
-    scores: [isize; 2]
+    scores: [isize; 2],
+
+    /// The side of the player serving the ball, if any.
+    serving_side: Option<FieldSide>,
+
+    /// Mark the score of the serving player.
+    show_serving_side: bool,
 }
 
 impl Scoreboard {
-    /// Initialize a new scoreboard with a given `size` (`[width, height]`) and a `title`.
-    pub fn new(size: [u32; 2], title: &str) -> Scoreboard {
+    /// Initialize a new scoreboard with a given `size` (`[width, height]`) and a `title`, customized by `config`.
+    pub fn new(size: [u32; 2], title: &str, config: &Config) -> Scoreboard {
         Scoreboard {
             title: String::from(title),
+            cached_layout: None,
             height: size[1],
+            large_text: config.large_text,
+            max_score_digits: config.max_score_digits,
             width: size[0],
-            scores: [0, 0]
+            scores: [0, 0],
+            serving_side: None,
+            show_serving_side: config.show_serving_side,
         }
     }
 
     /// Determine the font size based on the height of the scoreboard.
+    ///
+    /// Usually, the font size is half the height. For large text, it is 70% of the height (computed without the risk
+    /// of an overflow).
     fn determine_font_size(&self) -> u32 {
-        self.height / 2
+        if self.large_text {
+            self.height / 10 * 7 + self.height % 10 * 7 / 10
+        }
+        else {
+            self.height / 2
+        }
     }
 
-    /// Draw the given `text` aligned at `position_x` on the screen. The text is always vertically aligned at the middle
-    /// of the scoreboard.
-    fn draw_text(&self, text: &str, alignment: &TextAlignment, position_x: f64, font: &mut Glyphs,
-                 context: &Context, graphics: &mut G2d) {
-        let size: u32 = self.determine_font_size();
-        let width: f64 = font.width(size, text).unwrap_or(0.0);
-
-        // The vertical alignment is the middle of the scoreboard. The y-position is the baseline of the text.
-        let y: f64 = f64::from(self.height + size) / 2.0;
-        let x: f64 = alignment.align(position_x, width);
-        let transformation = context.transform.trans(x, y);
+    /// Format the `score` for display, abbreviating it if it has too many digits.
+    fn format_score(&self, score: isize) -> String {
+        let score_text: String = score.to_string();
+        if score_text.trim_start_matches('-').len() > self.max_score_digits {
+            abbreviate_score(score)
+        }
+        else {
+            score_text
+        }
+    }
 
-        let text_object = Text::new_color(color::WHITE, size);
-        let _ = text_object.draw(text, font, &context.draw_state, transformation, graphics);
+    /// Draw the positioned `text` on the screen, snapped to whole pixels if a `pixel_scale` is given.
+    fn draw_text(&self, text: &PositionedText, font: &mut Glyphs, context: &Context, graphics: &mut G2d,
+                 pixel_scale: Option<f64>) {
+        let position: (f64, f64) = pixels::snap_point((text.x, text.y), pixel_scale);
+        let transformation = context.transform.trans(position.0, position.1);
+        let text_object = Text::new_color(color::WHITE, self.determine_font_size());
+        let _ = text_object.draw(&text.text, font, &context.draw_state, transformation, graphics);
+
+        // Draw the text a second time, slightly shifted, to make it bold.
+        if self.large_text {
+            let transformation = transformation.trans(1.0, 0.0);
+            let _ = text_object.draw(&text.text, font, &context.draw_state, transformation, graphics);
+        }
     }
 
-    /// Render the scoreboard.
-    pub fn on_render(&self, font: &mut Glyphs, context: Context, graphics: &mut G2d) {
+    /// Position all texts on the scoreboard: the title, the left score, and the right score.
+    fn layout<M: FontMetrics>(&self, metrics: &mut M) -> [PositionedText; 3] {
         let center: f64 = f64::from(self.width) / 2.0;
-        let left_margin: f64 = 10.0;
-        let right_margin: f64 = f64::from(self.width) - left_margin;
-
-        // Draw the title.
-        self.draw_text(&self.title, &TextAlignment::Center, center, font, &context, graphics);
+        let right_margin: f64 = f64::from(self.width) - MARGIN;
+
+        // Mark the serving player's score on the side facing the center.
+        let mut left_score: String = self.format_score(self.scores[0]);
+        let mut right_score: String = self.format_score(self.scores[1]);
+        if self.show_serving_side {
+            match self.serving_side {
+                Some(FieldSide::Left) => left_score = format!("{} {}", left_score, SERVING_MARKER),
+                Some(FieldSide::Right) => right_score = format!("{} {}", SERVING_MARKER, right_score),
+                None => {},
+            }
+        }
 
-        // Draw the left score.
-        let score: &str = &self.scores[0].to_string();
-        self.draw_text(score, &TextAlignment::Left, left_margin, font, &context, graphics);
+        [
+            self.position_text(&self.title, &TextAlignment::Center, center, metrics),
+            self.position_text(&left_score, &TextAlignment::Left, MARGIN, metrics),
+            self.position_text(&right_score, &TextAlignment::Right, right_margin, metrics),
+        ]
+    }
 
-        // Draw the right score.
-        let score: &str = &self.scores[1].to_string();
-        self.draw_text(score, &TextAlignment::Right, right_margin, font, &context, graphics);
+    /// Render the scoreboard. If a `pixel_scale` (device pixels per point) is given, the texts are snapped to whole
+    /// pixels.
+    pub fn on_render(&mut self, font: &mut Glyphs, context: Context, graphics: &mut G2d, pixel_scale: Option<f64>) {
+        self.update_layout(font);
+        if let Some(ref texts) = self.cached_layout {
+            for text in texts {
+                self.draw_text(text, font, &context, graphics, pixel_scale);
+            }
+        }
     }
 
     /// Resize the scoreboard.
     pub fn on_resize(&mut self, new_width: u32, new_height: u32) {
         self.width = new_width;
         self.height = new_height;
+        self.cached_layout = None;
     }
 
-    /// Update the scoreboard.
-    pub fn on_update(&mut self, scores: [isize; 2]) {
-        self.scores = scores;
+    /// Update the scoreboard with the players' `scores` and the side of the player serving the ball, if any. The
+    /// layout is only recomputed if anything changed.
+    pub fn on_update(&mut self, scores: [isize; 2], serving_side: Option<FieldSide>) {
+        if scores != self.scores || serving_side != self.serving_side {
+            self.scores = scores;
+            self.serving_side = serving_side;
+            self.cached_layout = None;
+        }
+    }
+
+    /// Recompute the layout of the texts if it is outdated.
+    fn update_layout<M: FontMetrics>(&mut self, metrics: &mut M) {
+        if self.cached_layout.is_none() {
+            self.cached_layout = Some(self.layout(metrics));
+        }
+    }
+
+    /// Position the given `text` aligned at `position_x`. The text is always vertically aligned at the middle of the
+    /// scoreboard.
+    fn position_text<M: FontMetrics>(&self, text: &str, alignment: &TextAlignment, position_x: f64, metrics: &mut M)
+        -> PositionedText {
+        let size: u32 = self.determine_font_size();
+        let width: f64 = metrics.text_width(size, text);
+
+        // The vertical alignment is the middle of the scoreboard. The y-position is the baseline of the text.
+        PositionedText {
+            text: String::from(text),
+            x: alignment.align(position_x, width),
+            y: f64::from(self.height + size) / 2.0,
+            width,
+        }
     }
 }
 
@@ -122,6 +263,29 @@ mod tests {
 
     use super::*;
 
+    /// Font metrics of a monospaced font where each character is half as wide as the font size.
+    struct Monospace;
+
+    impl FontMetrics for Monospace {
+        fn text_width(&mut self, size: u32, text: &str) -> f64 {
+            f64::from(size) / 2.0 * (text.chars().count() as f64)
+        }
+    }
+
+    /// Monospaced font metrics counting how often the width of a text is measured.
+    #[derive(Default)]
+    struct CountingMonospace {
+        /// The number of measured texts.
+        measurements: usize,
+    }
+
+    impl FontMetrics for CountingMonospace {
+        fn text_width(&mut self, size: u32, text: &str) -> f64 {
+            self.measurements += 1;
+            Monospace.text_width(size, text)
+        }
+    }
+
     #[test]
     fn align_left() {
         let alignment = TextAlignment::Left;
@@ -143,9 +307,42 @@ mod tests {
         assert_eq!(x, 30.0);
     }
 
+    #[test]
+    fn abbreviate_score_small() {
+        assert_eq!(abbreviate_score(0), "0");
+        assert_eq!(abbreviate_score(999), "999");
+        assert_eq!(abbreviate_score(-999), "-999");
+    }
+
+    #[test]
+    fn abbreviate_score_boundaries() {
+        assert_eq!(abbreviate_score(1_000), "1.0K");
+        assert_eq!(abbreviate_score(999_999), "999.9K");
+        assert_eq!(abbreviate_score(1_000_000), "1.0M");
+        assert_eq!(abbreviate_score(1_234_567), "1.2M");
+        assert_eq!(abbreviate_score(999_999_999), "999.9M");
+        assert_eq!(abbreviate_score(1_000_000_000), "1.0B");
+        assert_eq!(abbreviate_score(-1_500_000_000), "-1.5B");
+    }
+
+    #[test]
+    fn abbreviate_score_extremes() {
+        assert_eq!(abbreviate_score(::std::isize::MAX), "9.2Qi");
+        assert_eq!(abbreviate_score(::std::isize::MIN), "-9.2Qi");
+    }
+
+    #[test]
+    fn format_score() {
+        let config = Config { max_score_digits: 4, ..Config::default() };
+        let scoreboard = Scoreboard::new([200, 100], "Mief", &config);
+        assert_eq!(scoreboard.format_score(9_999), "9999");
+        assert_eq!(scoreboard.format_score(-9_999), "-9999");
+        assert_eq!(scoreboard.format_score(12_345), "12.3K");
+    }
+
     #[test]
     fn new() {
-        let scoreboard = Scoreboard::new([200, 100], "Mief");
+        let scoreboard = Scoreboard::new([200, 100], "Mief", &Config::default());
         assert_eq!(scoreboard.title, String::from("Mief"));
         assert_eq!(scoreboard.width, 200);
         assert_eq!(scoreboard.height, 100);
@@ -153,7 +350,7 @@ mod tests {
 
     quickcheck! {
         fn determine_font_size(height: u32) -> bool {
-            let scoreboard = Scoreboard::new([100, height], "Mief");
+            let scoreboard = Scoreboard::new([100, height], "Mief", &Config::default());
             let font_size: u32 = scoreboard.determine_font_size();
 
             if height % 2 == 0 {
@@ -165,9 +362,54 @@ mod tests {
         }
     }
 
+    quickcheck! {
+        fn determine_font_size_large_text(height: u32) -> bool {
+            let config = Config { large_text: true, ..Config::default() };
+            let scoreboard = Scoreboard::new([100, height], "Mief", &config);
+            let font_size: u32 = scoreboard.determine_font_size();
+
+            // The font size must be 70% of the height (rounded down), but must never exceed the height.
+            u64::from(font_size) == u64::from(height) * 7 / 10 && font_size <= height
+        }
+    }
+
+    #[test]
+    fn determine_font_size_large_text_bigger() {
+        let config = Config { large_text: true, ..Config::default() };
+        let scoreboard = Scoreboard::new([200, 120], "Mief", &config);
+        assert_eq!(scoreboard.determine_font_size(), 84);
+
+        let scoreboard = Scoreboard::new([200, 120], "Mief", &Config::default());
+        assert_eq!(scoreboard.determine_font_size(), 60);
+    }
+
+    #[test]
+    fn layout() {
+        let mut scoreboard = Scoreboard::new([800, 120], "Mief", &Config::default());
+        scoreboard.on_update([12_345, -12_345], None);
+        let [title, left, right] = scoreboard.layout(&mut Monospace);
+
+        // All texts have the correct content and are vertically aligned on the same baseline.
+        assert_eq!(title.text, "Mief");
+        assert_eq!(left.text, "12345");
+        assert_eq!(right.text, "-12345");
+        assert_eq!(title.y, 90.0);
+        assert_eq!(left.y, title.y);
+        assert_eq!(right.y, title.y);
+
+        // The title is centered, the left score starts at the left margin, the right score ends at the right margin.
+        assert_eq!(title.x + title.width / 2.0, 400.0);
+        assert_eq!(left.x, MARGIN);
+        assert_eq!(right.x + right.width, 800.0 - MARGIN);
+
+        // The texts do not overlap.
+        assert!(left.x + left.width < title.x);
+        assert!(title.x + title.width < right.x);
+    }
+
     #[test]
     fn on_resize() {
-        let mut scoreboard = Scoreboard::new([200, 100], "Mief");
+        let mut scoreboard = Scoreboard::new([200, 100], "Mief", &Config::default());
         scoreboard.on_resize(100, 200);
         assert_eq!(scoreboard.width, 100);
         assert_eq!(scoreboard.height, 200);
@@ -175,8 +417,84 @@ mod tests {
 
     #[test]
     fn on_update() {
-        let mut scoreboard = Scoreboard::new([200, 100], "Mief");
-        scoreboard.on_update([42, -42]);
+        let mut scoreboard = Scoreboard::new([200, 100], "Mief", &Config::default());
+        scoreboard.on_update([42, -42], Some(FieldSide::Left));
         assert_eq!(scoreboard.scores, [42, -42]);
+        assert_eq!(scoreboard.serving_side, Some(FieldSide::Left));
+    }
+
+    #[test]
+    fn on_update_unchanged() {
+        let mut scoreboard = Scoreboard::new([200, 100], "Mief", &Config::default());
+        scoreboard.on_update([1, 2], Some(FieldSide::Left));
+        scoreboard.update_layout(&mut Monospace);
+        assert!(scoreboard.cached_layout.is_some());
+
+        scoreboard.on_update([1, 2], Some(FieldSide::Left));
+        assert!(scoreboard.cached_layout.is_some());
+    }
+
+    #[test]
+    fn on_update_changed() {
+        let mut scoreboard = Scoreboard::new([200, 100], "Mief", &Config::default());
+        scoreboard.update_layout(&mut Monospace);
+        scoreboard.on_update([1, 0], None);
+        assert!(scoreboard.cached_layout.is_none());
+
+        scoreboard.update_layout(&mut Monospace);
+        scoreboard.on_update([1, 0], Some(FieldSide::Right));
+        assert!(scoreboard.cached_layout.is_none());
+    }
+
+    #[test]
+    fn on_resize_outdates_layout() {
+        let mut scoreboard = Scoreboard::new([200, 100], "Mief", &Config::default());
+        scoreboard.update_layout(&mut Monospace);
+        scoreboard.on_resize(100, 200);
+        assert!(scoreboard.cached_layout.is_none());
+    }
+
+    #[test]
+    fn update_layout_cached() {
+        let mut scoreboard = Scoreboard::new([800, 120], "Mief", &Config::default());
+        let mut metrics = CountingMonospace::default();
+        scoreboard.update_layout(&mut metrics);
+        scoreboard.update_layout(&mut metrics);
+        assert_eq!(metrics.measurements, 3);
+        assert_eq!(scoreboard.cached_layout, Some(scoreboard.layout(&mut Monospace)));
+
+        scoreboard.on_update([1, 0], None);
+        scoreboard.update_layout(&mut metrics);
+        assert_eq!(metrics.measurements, 6);
+    }
+
+    #[test]
+    fn layout_serving_side() {
+        let config = Config { show_serving_side: true, ..Config::default() };
+        let mut scoreboard = Scoreboard::new([800, 120], "Mief", &config);
+
+        scoreboard.on_update([1, 2], Some(FieldSide::Left));
+        let [_, left, right] = scoreboard.layout(&mut Monospace);
+        assert_eq!(left.text, "1 *");
+        assert_eq!(right.text, "2");
+
+        scoreboard.on_update([1, 2], Some(FieldSide::Right));
+        let [_, left, right] = scoreboard.layout(&mut Monospace);
+        assert_eq!(left.text, "1");
+        assert_eq!(right.text, "* 2");
+        assert_eq!(right.x + right.width, 800.0 - MARGIN);
+
+        scoreboard.on_update([1, 2], None);
+        let [_, left, right] = scoreboard.layout(&mut Monospace);
+        assert_eq!(left.text, "1");
+        assert_eq!(right.text, "2");
+    }
+
+    #[test]
+    fn layout_serving_side_hidden() {
+        let mut scoreboard = Scoreboard::new([800, 120], "Mief", &Config::default());
+        scoreboard.on_update([1, 2], Some(FieldSide::Left));
+        let [_, left, _] = scoreboard.layout(&mut Monospace);
+        assert_eq!(left.text, "1");
     }
 }
@@ -7,6 +7,7 @@
 //! Error handling.
 
 use std::fmt;
+use std::io;
 
 use find_folder::Error as FindFolderError;
 
@@ -16,6 +17,12 @@ pub type Result<T> = ::std::result::Result<T, Error>;
 /// A wrapper type for all errors caused by _Mief_.
 #[derive(Debug)]
 pub enum Error {
+    /// Errors caused by invalid settings.
+    Config(String),
+
+    /// Errors caused by reading or writing files.
+    File(io::Error),
+
     /// Errors caused by faulty I/O operations.
     IO(FindFolderError),
 
@@ -26,6 +33,8 @@ pub enum Error {
 impl fmt::Display for Error {
     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            Error::Config(ref error) => error.fmt(formatter),
+            Error::File(ref error) => error.fmt(formatter),
             Error::IO(ref error) => error.fmt(formatter),
             Error::Piston(ref error) => error.fmt(formatter),
         }
@@ -35,6 +44,8 @@ impl fmt::Display for Error {
 impl ::std::error::Error for Error {
     fn cause(&self) -> Option<&::std::error::Error> {
         match *self {
+            Error::Config(_) => None,
+            Error::File(ref error) => Some(error),
             Error::IO(ref error) => Some(error),
             Error::Piston(_) => None,
         }
@@ -42,6 +53,8 @@ impl ::std::error::Error for Error {
 
     fn description(&self) -> &str {
         match *self {
+            Error::Config(ref error) => error,
+            Error::File(ref error) => error.description(),
             Error::IO(ref error) => error.description(),
             Error::Piston(ref error) => error,
         }
@@ -54,6 +67,12 @@ impl From<FindFolderError> for Error {
     }
 }
 
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::File(error)
+    }
+}
+
 impl From<String> for Error {
     fn from(error: String) -> Error {
         Error::Piston(error)
@@ -63,9 +82,23 @@ impl From<String> for Error {
 #[cfg(test)]
 mod tests {
     use std::error::Error as ErrorTrait;
+    use std::io;
     use find_folder::Error as FindFolderError;
     use super::*;
 
+    #[test]
+    fn cause_config() {
+        let message: &str = "Config Failure";
+        let error = Error::Config(String::from(message));
+        assert!(error.cause().is_none(), "Config errors do not have a cause.");
+    }
+
+    #[test]
+    fn cause_file() {
+        let error = Error::File(io::Error::new(io::ErrorKind::NotFound, "File Failure"));
+        assert!(error.cause().is_some(), "File errors have a cause.");
+    }
+
     #[test]
     fn cause_io() {
         let error = Error::IO(FindFolderError::NotFound);
@@ -79,6 +112,13 @@ mod tests {
         assert!(error.cause().is_none(), "Piston errors do not have a cause.");
     }
 
+    #[test]
+    fn description_config() {
+        let message: &str = "Config Failure";
+        let error = Error::Config(String::from(message));
+        assert_eq!(error.description(), String::from(message));
+    }
+
     #[test]
     fn description_io() {
         let error = Error::IO(FindFolderError::NotFound);
@@ -92,6 +132,20 @@ mod tests {
         assert_eq!(error.description(), String::from(message));
     }
 
+    #[test]
+    fn fmt_display_config() {
+        let message: &str = "Config Failure";
+        let error = Error::Config(String::from(message));
+        assert_eq!(format!("{}", error), message);
+    }
+
+    #[test]
+    fn fmt_display_file() {
+        let message: &str = "File Failure";
+        let error = Error::File(io::Error::new(io::ErrorKind::NotFound, message));
+        assert_eq!(format!("{}", error), message);
+    }
+
     #[test]
     fn fmt_display_io() {
         let error = Error::IO(FindFolderError::NotFound);
@@ -115,6 +169,16 @@ mod tests {
         assert!(is_io_error, "Expected IO failure.");
     }
 
+    #[test]
+    fn from_io_error() {
+        let error = io::Error::new(io::ErrorKind::NotFound, "File Failure");
+        let mut is_file_error: bool = false;
+        if let Error::File(_) = Error::from(error) {
+            is_file_error = true;
+        }
+        assert!(is_file_error, "Expected file failure.");
+    }
+
     #[test]
     fn from_string() {
         let message = String::from("Piston Failure");
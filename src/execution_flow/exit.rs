@@ -22,6 +22,15 @@ pub enum Code {
 
     /// Failure during I/O operations (Code: `2`).
     IOFailure = 2,
+
+    /// Failure due to invalid settings (Code: `3`).
+    ConfigFailure = 3,
+
+    /// Failure while reading or writing files (Code: `4`).
+    FileFailure = 4,
+
+    /// Expected execution, but the match was abandoned before any point was scored (Code: `5`).
+    Abandoned = 5,
 }
 
 impl From<Code> for i32 {
@@ -33,6 +42,8 @@ impl From<Code> for i32 {
 /// Quit the program execution. The exit code and message are chosen based on `error`.
 pub fn fail_from_error(error: Error) -> ! {
     match error {
+        Error::Config(message) => fail_with_message(Code::ConfigFailure, &message),
+        Error::File(error) => fail_with_message(Code::FileFailure, &error.to_string()),
         Error::IO(error) => fail_with_message(Code::IOFailure, error.description()),
         Error::Piston(message) => fail_with_message(Code::PistonFailure, &message)
     }
@@ -50,7 +61,7 @@ pub fn succeed() -> ! {
 }
 
 /// Quit the program execution with the given code.
-fn quit<I: Into<i32>>(code: I) -> ! {
+pub fn quit<I: Into<i32>>(code: I) -> ! {
     process::exit(code.into())
 }
 
@@ -72,4 +83,19 @@ mod tests {
     fn exit_code_io_failure() {
         assert_eq!(2, Code::IOFailure.into());
     }
+
+    #[test]
+    fn exit_code_config_failure() {
+        assert_eq!(3, Code::ConfigFailure.into());
+    }
+
+    #[test]
+    fn exit_code_file_failure() {
+        assert_eq!(4, Code::FileFailure.into());
+    }
+
+    #[test]
+    fn exit_code_abandoned() {
+        assert_eq!(5, Code::Abandoned.into());
+    }
 }
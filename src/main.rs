@@ -33,20 +33,48 @@ extern crate quickcheck;
 extern crate rand;
 
 mod application;
+mod camera;
+mod config;
+mod controls;
+mod effects;
 mod elements;
 mod execution_flow;
 mod color;
+mod pixels;
+
+use std::env;
+use std::io;
 
 use application::Application;
+use application::Outcome;
 use execution_flow::exit;
+use execution_flow::exit::Code;
 
 /// Run _Mief_.
 fn main() {
+    if env::args().skip(1).any(|argument| argument == application::CHECK_ARGUMENT) {
+        match application::check_environment() {
+            Ok(()) => exit::succeed(),
+            Err(error) => exit::fail_from_error(error),
+        }
+    }
+
+    match application::find_dump_frames(env::args().skip(1)) {
+        Ok(Some(frames)) => {
+            match application::dump_game(frames, &mut io::stdout()) {
+                Ok(()) => exit::succeed(),
+                Err(error) => exit::fail_from_error(error),
+            }
+        },
+        Ok(None) => {},
+        Err(error) => exit::fail_from_error(error),
+    }
+
     let mut application = match Application::new() {
         Ok(application) => application,
         Err(error) => exit::fail_from_error(error),
     };
-    application.run();
+    let outcome: Outcome = application.run();
 
-    exit::succeed();
+    exit::quit(Code::from(outcome));
 }
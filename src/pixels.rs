@@ -0,0 +1,60 @@
+// Copyright 2017 Bastian Meyer
+//
+// Licensed under the Apache License, Version 2.0, <LICENSE-APACHE or http://apache.org/licenses/LICENSE-2.0> or the
+// MIT license <LICENSE-MIT or http://opensource.org/licenses/MIT>, at your option. This file may not be copied,
+// modified, or distributed except according to those terms.
+
+//! Snapping of drawn coordinates to whole device pixels, giving a crisp look on high-DPI displays.
+
+/// Round `value` (in points) to the nearest whole device pixel, with `scale` device pixels per point. Values halfway
+/// between two pixels are always rounded up, so positive and negative values are snapped alike.
+pub fn snap(value: f64, scale: f64) -> f64 {
+    (value * scale + 0.5).floor() / scale
+}
+
+/// Snap the `point` `(x, y)` to whole device pixels if a `scale` is given (see `snap`). Otherwise, the point is kept.
+pub fn snap_point(point: (f64, f64), scale: Option<f64>) -> (f64, f64) {
+    match scale {
+        Some(scale) => (snap(point.0, scale), snap(point.1, scale)),
+        None => point,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_positive() {
+        assert_eq!(snap(3.0, 1.0), 3.0);
+        assert_eq!(snap(3.2, 1.0), 3.0);
+        assert_eq!(snap(3.5, 1.0), 4.0);
+        assert_eq!(snap(3.7, 1.0), 4.0);
+    }
+
+    #[test]
+    fn snap_negative() {
+        assert_eq!(snap(-3.2, 1.0), -3.0);
+        assert_eq!(snap(-3.5, 1.0), -3.0);
+        assert_eq!(snap(-3.7, 1.0), -4.0);
+        assert_eq!(snap(-0.2, 1.0), 0.0);
+    }
+
+    #[test]
+    fn snap_scaled() {
+        // With two device pixels per point, coordinates snap to half points.
+        assert_eq!(snap(3.2, 2.0), 3.0);
+        assert_eq!(snap(3.3, 2.0), 3.5);
+        assert_eq!(snap(-3.3, 2.0), -3.5);
+        assert_eq!(snap(-3.75, 2.0), -3.5);
+
+        // With 1.5 device pixels per point, coordinates snap to multiples of 2/3 of a point.
+        assert!((snap(1.0, 1.5) - 4.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn snap_point_optional() {
+        assert_eq!(snap_point((1.2, -1.7), Some(1.0)), (1.0, -2.0));
+        assert_eq!(snap_point((1.2, -1.7), None), (1.2, -1.7));
+    }
+}